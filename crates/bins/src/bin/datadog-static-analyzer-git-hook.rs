@@ -8,18 +8,19 @@ use cli::constants::{
 };
 use cli::datadog_utils::{get_all_default_rulesets, get_rules_from_rulesets, get_secrets_rules};
 use cli::file_utils::{
-    filter_files_by_size, filter_files_for_language, get_files, read_files_from_gitignore,
+    filter_files_by_size, filter_files_for_language, get_files, read_path_config_from_gitignore,
 };
 use cli::git_utils::{
     get_changed_files_between_shas, get_changed_files_with_branch, get_default_branch,
 };
 use cli::model::cli_configuration::CliConfiguration;
+use cli::output::FileSink;
 use cli::rule_utils::{
     check_rules_checksum, convert_rules_to_rules_internal, get_languages_for_rules,
 };
-use cli::sarif::sarif_utils::{generate_sarif_file, SarifReportMetadata};
+use cli::sarif::sarif_utils::{write_sarif_file, SarifReportMetadata};
 use cli::utils::{choose_cpu_count, get_num_threads_to_use, print_configuration};
-use common::analysis_options::AnalysisOptions;
+use common::analysis_options::{AnalysisOptions, RuleTimeoutAction};
 use common::model::diff_aware::DiffAware;
 use getopts::Options;
 use git2::Repository;
@@ -39,7 +40,6 @@ use secrets::scanner::{build_sds_scanner, find_secrets};
 use secrets::secret_files::should_ignore_file_for_secret;
 use std::cell::Cell;
 use std::collections::HashMap;
-use std::io::Write;
 use std::path::PathBuf;
 use std::process::exit;
 use std::sync::Arc;
@@ -329,11 +329,9 @@ fn main() -> Result<()> {
 
     // ignore all directories that are in gitignore
     if !ignore_gitignore {
-        let paths_from_gitignore = read_files_from_gitignore(directory_to_analyze.as_str())
+        let gitignore = read_path_config_from_gitignore(directory_to_analyze.as_str())
             .expect("error when reading gitignore file");
-        path_config
-            .ignore
-            .extend(paths_from_gitignore.iter().map(|p| p.clone().into()));
+        path_config.ignore.extend(gitignore.ignore);
     }
 
     let files_in_repository = get_files(directory_to_analyze.as_str(), vec![], &path_config)
@@ -362,7 +360,7 @@ fn main() -> Result<()> {
         num_cpus,
         rules: rules.clone(),
         rule_config_provider,
-        output_file: "".to_string(),
+        output_file: None,
         max_file_size_kb,
         use_staging,
         show_performance_statistics: false,
@@ -370,10 +368,11 @@ fn main() -> Result<()> {
         secrets_enabled,
         static_analysis_enabled,
         secrets_rules: secrets_rules.clone(),
+        max_findings: None,
     };
 
     if configuration.use_debug {
-        print_configuration(&configuration);
+        print_configuration(&mut io::stdout(), &configuration)?;
     }
 
     let timeout = matches
@@ -390,6 +389,8 @@ fn main() -> Result<()> {
         use_debug,
         ignore_generated_files,
         timeout,
+        rule_timeout_action: RuleTimeoutAction::default(),
+        source_map_directory: None,
     };
 
     if should_verify_checksum {
@@ -525,7 +526,7 @@ fn main() -> Result<()> {
                         let res = if let Ok(file_content) = fs::read_to_string(&path) {
                             let mut opt = JS_RUNTIME.replace(None);
                             let runtime_ref = opt.get_or_insert_with(|| {
-                                v8.try_new_runtime().expect("ddsa init should succeed")
+                                v8.try_new_warm_runtime().expect("ddsa init should succeed")
                             });
 
                             let file_content = Arc::from(file_content);
@@ -695,7 +696,8 @@ fn main() -> Result<()> {
 
     // Write the results to a SARIF file is necessary
     if let Some(output_file) = output_opt {
-        let sarif_content = generate_sarif_file(
+        write_sarif_file(
+            &FileSink(PathBuf::from(output_file)),
             &configuration,
             all_rule_results,
             secrets_results,
@@ -705,14 +707,12 @@ fn main() -> Result<()> {
                 config_digest: configuration.generate_diff_aware_digest(),
                 diff_aware_parameters: None,
                 execution_time_secs: analysis_start_instant.elapsed().as_secs(),
+                level_mapping: HashMap::new(),
+                findings_truncated: false,
             },
             &all_path_metadata,
         )
-        .expect("cannot generate SARIF results");
-
-        let mut file = fs::File::create(output_file).context("cannot create file")?;
-        file.write_all(sarif_content.as_bytes())
-            .context("error when writing results")?;
+        .context("error when writing results")?;
     }
 
     // Logic to handle if the run failed or not and show the confirmation
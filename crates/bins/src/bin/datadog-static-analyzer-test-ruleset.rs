@@ -1,5 +1,5 @@
 use cli::datadog_utils::get_ruleset;
-use common::analysis_options::AnalysisOptions;
+use common::analysis_options::{AnalysisOptions, RuleTimeoutAction};
 use kernel::analysis::analyze::analyze_with;
 use kernel::model::rule::Rule;
 
@@ -28,6 +28,8 @@ fn test_rule(runtime: &mut JsRuntime, rule: &Rule, test: &RuleTest) -> Result<St
         use_debug: true,
         ignore_generated_files: false,
         timeout: None,
+        rule_timeout_action: RuleTimeoutAction::default(),
+        source_map_directory: None,
     };
     let rules = vec![rule_internal];
     let analyze_result = analyze_with(
@@ -4,51 +4,55 @@ use indicatif::ProgressBar;
 use itertools::Itertools;
 use rayon::prelude::*;
 use std::collections::HashMap;
-use std::io::prelude::*;
-use std::path::PathBuf;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
 use std::process::exit;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
 use std::sync::Arc;
 use std::time::{Duration, Instant, SystemTime};
-use std::{env, fs};
+use std::{env, fs, thread};
 
-use cli::config_file::get_config;
+use cli::config_file::{get_config, read_config_file};
 use cli::constants::{
-    DEFAULT_MAX_CPUS, DEFAULT_MAX_FILE_SIZE_KB, EXIT_CODE_FAIL_ON_VIOLATION,
-    EXIT_CODE_INVALID_CONFIGURATION, EXIT_CODE_INVALID_DIRECTORY, EXIT_CODE_NO_DIRECTORY,
-    EXIT_CODE_NO_OUTPUT, EXIT_CODE_RULESET_NOT_FOUND, EXIT_CODE_RULE_CHECKSUM_INVALID,
-    EXIT_CODE_RULE_FILE_WITH_CONFIGURATION, EXIT_CODE_UNSAFE_SUBDIRECTORIES,
+    DEFAULT_MAX_CPUS, EXIT_CODE_FAIL_ON_VIOLATION, EXIT_CODE_INVALID_CONFIGURATION,
+    EXIT_CODE_INVALID_DIRECTORY, EXIT_CODE_NO_DIRECTORY, EXIT_CODE_RULESET_NOT_FOUND,
+    EXIT_CODE_RULE_CHECKSUM_INVALID, EXIT_CODE_RULE_FILE_WITH_CONFIGURATION,
+    EXIT_CODE_UNSAFE_SUBDIRECTORIES,
 };
 use cli::csv;
-use cli::datadog_utils::{
-    get_all_default_rulesets, get_diff_aware_information, get_rules_from_rulesets,
-    get_secrets_rules, DatadogApiError,
-};
+use cli::csv::{parse_csv_columns, CsvColumn};
+use cli::datadog_utils::get_diff_aware_information;
 use cli::file_utils::{
     are_subdirectories_safe, filter_files_by_diff_aware_info, filter_files_by_size,
-    filter_files_for_language, get_files, read_files_from_gitignore,
+    filter_files_for_language, get_files,
 };
-use cli::model::cli_configuration::CliConfiguration;
+use cli::json;
+use cli::model::cli_configuration::{CliConfigError, CliConfiguration, ParsedCliArgs};
 use cli::model::datadog_api::DiffAwareData;
+use cli::model::rule_filter::RuleFilter;
+use cli::output::{FileSink, OutputSink, StdoutSink};
 use cli::rule_utils::{
-    check_rules_checksum, convert_rules_to_rules_internal, convert_secret_result_to_rule_result,
-    count_violations_by_severities, get_languages_for_rules, get_rulesets_from_file,
+    check_rules_checksum, convert_rules_to_rules_internal, count_violations_by_severities,
+    get_languages_for_rules, truncate_rule_results,
 };
-use cli::sarif::sarif_utils::{generate_sarif_file, SarifReportMetadata};
-use cli::utils::{choose_cpu_count, get_num_threads_to_use, print_configuration};
+use cli::sarif::sarif_utils::{parse_sarif_level_mapping, write_sarif_file, SarifReportMetadata};
+use cli::source_map::translate_rule_results;
+use cli::utils::{get_num_threads_to_use, print_configuration};
 use cli::violations_table;
-use common::analysis_options::AnalysisOptions;
+use cli::watch::FileWatcher;
+use common::analysis_options::{AnalysisOptions, RuleTimeoutAction};
 use common::model::diff_aware::DiffAware;
 use kernel::analysis::analyze::{analyze_with, generate_flow_graph_dot};
-use kernel::analysis::ddsa_lib::v8_platform::initialize_v8;
+use kernel::analysis::ddsa_lib::metrics::PrintMetricsSink;
+use kernel::analysis::ddsa_lib::v8_platform::{initialize_v8, Initialized, V8Platform};
 use kernel::analysis::ddsa_lib::JsRuntime;
-use kernel::analysis::generated_content::DEFAULT_IGNORED_GLOBS;
 use kernel::classifiers::{is_test_file, ArtifactClassification};
+use kernel::config_file::config_file_to_yaml;
 use kernel::constants::{CARGO_VERSION, VERSION};
 use kernel::model::analysis::ERROR_RULE_TIMEOUT;
 use kernel::model::common::{Language, OutputFormat};
-use kernel::model::config_file::{ConfigFile, ConfigMethod, PathConfig};
-use kernel::model::rule::{Rule, RuleInternal, RuleResult, RuleSeverity};
-use kernel::rule_config::RuleConfigProvider;
+use kernel::model::config_file::{ConfigFile, ConfigMethod};
+use kernel::model::rule::{RuleInternal, RuleResult, RuleSeverity};
 use secrets::model::secret_result::{SecretResult, SecretValidationStatus};
 use secrets::scanner::{build_sds_scanner, find_secrets};
 use secrets::secret_files::should_ignore_file_for_secret;
@@ -77,14 +81,81 @@ fn read_file_with_fallback_encoding(file_path: &PathBuf) -> Result<String> {
     }
 }
 
+/// The delay during which a file must not change before it is analyzed in `--watch` mode.
+const WATCH_DEBOUNCE: Duration = Duration::from_millis(200);
+/// The interval at which the files are checked for changes in `--watch` mode.
+const WATCH_POLL_INTERVAL: Duration = Duration::from_millis(50);
+
+/// Analyzes the files of the repository again whenever they change, and writes the results of
+/// each file as a line of JSON to stdout. Each line is flushed once written, so no result is lost
+/// when the process is interrupted (SIGINT).
+fn watch_files(
+    configuration: &CliConfiguration,
+    languages: &[Language],
+    subdirectories: Vec<String>,
+    v8: &V8Platform<Initialized>,
+    analysis_options: &AnalysisOptions,
+) -> Result<()> {
+    let directory_path = Path::new(&configuration.source_directory);
+    let rules_by_language = languages
+        .iter()
+        .map(|language| {
+            let rules = convert_rules_to_rules_internal(configuration, language)?;
+            Ok((*language, rules))
+        })
+        .collect::<Result<Vec<_>>>()?;
+    let mut watcher = FileWatcher::new(
+        &configuration.source_directory,
+        subdirectories,
+        configuration.path_config.clone(),
+        WATCH_DEBOUNCE,
+    )?;
+    let mut runtime = v8.try_new_warm_runtime().expect("ddsa init should succeed");
+    eprintln!("Watching {} for changes", configuration.source_directory);
+
+    loop {
+        thread::sleep(WATCH_POLL_INTERVAL);
+        let changed_files = filter_files_by_size(&watcher.poll()?, configuration);
+        for (language, rules) in &rules_by_language {
+            for path in filter_files_for_language(&changed_files, language) {
+                let Ok(file_content) = read_file_with_fallback_encoding(&path) else {
+                    eprintln!("error when getting content of path {}", &path.display());
+                    continue;
+                };
+                let relative_path = path
+                    .strip_prefix(directory_path)?
+                    .to_str()
+                    .context("path contains non-Unicode characters")?;
+                let rule_config = configuration
+                    .rule_config_provider
+                    .config_for_file(relative_path);
+                let mut results = analyze_with(
+                    &mut runtime,
+                    language,
+                    rules,
+                    &Arc::from(relative_path),
+                    &Arc::from(file_content),
+                    &rule_config,
+                    analysis_options,
+                );
+                results.retain(|r| !r.violations.is_empty() || !r.suppressed_violations.is_empty());
+                if let Some(source_map_directory) = &analysis_options.source_map_directory {
+                    results = translate_rule_results(results, source_map_directory);
+                }
+
+                let line = serde_json::json!({ "filename": relative_path, "results": results });
+                let mut stdout = io::stdout().lock();
+                writeln!(stdout, "{}", line)?;
+                stdout.flush()?;
+            }
+        }
+    }
+}
+
 fn main() -> Result<()> {
     let args: Vec<String> = env::args().collect();
     let program = args[0].clone();
     let mut opts = Options::new();
-    #[allow(unused_assignments)]
-    let mut ignore_gitignore = false;
-    let mut max_file_size_kb = DEFAULT_MAX_FILE_SIZE_KB;
-    let mut ignore_generated_files = true;
 
     opts.optopt(
         "i",
@@ -153,6 +224,18 @@ fn main() -> Result<()> {
         "path to ignore - the value is a glob",
         "**/test*.py (multiple values possible)",
     );
+    opts.optmulti(
+        "",
+        "include-rule",
+        "only use the rules matching this pattern - the value is <ruleset>/<rule> and accepts * wildcards",
+        "python-security/* (multiple values possible)",
+    );
+    opts.optmulti(
+        "",
+        "exclude-rule",
+        "rule to exclude - the value is <ruleset>/<rule> and accepts * wildcards",
+        "python-security/* (multiple values possible)",
+    );
     opts.optflag("h", "help", "print this help");
     opts.optflag("v", "version", "shows the tool version");
     opts.optflag(
@@ -172,12 +255,52 @@ fn main() -> Result<()> {
         "add-git-info",
         "add Git information to the SARIF report",
     );
+    opts.optopt(
+        "",
+        "max-findings",
+        "stop reporting static analysis violations once N violations have been found",
+        "N",
+    );
     opts.optopt(
         "",
         "rule-timeout-ms",
         "how long a rule can run before being killed, in milliseconds",
         "1000",
     );
+    opts.optopt(
+        "",
+        "rule-timeout-action",
+        "what to do when a rule times out: report an error, skip its results, or abort the analysis (default 'error')",
+        "error,skip,abort",
+    );
+    opts.optopt(
+        "",
+        "source-map-directory",
+        "directory containing the source maps of transpiled files; findings are reported in the original files",
+        "dist/maps",
+    );
+    opts.optflag(
+        "",
+        "generate-config",
+        "print a configuration file generated from the options and exit",
+    );
+    opts.optflag(
+        "",
+        "watch",
+        "analyze the files again when they change, and print the results of each file as a line of JSON",
+    );
+    opts.optopt(
+        "",
+        "csv-columns",
+        "columns of the CSV output, in order (default: all columns)",
+        "rule,filename,start_line,severity",
+    );
+    opts.optopt(
+        "",
+        "sarif-level-mapping",
+        "override the SARIF level reported for each severity",
+        "{\"Warning\":\"note\",\"Error\":\"error\"}",
+    );
 
     let matches = match opts.parse(&args[1..]) {
         Ok(m) => m,
@@ -197,12 +320,8 @@ fn main() -> Result<()> {
     }
 
     let diff_aware_requested = matches.opt_present("w");
-
-    if !matches.opt_present("o") {
-        eprintln!("output file not specified");
-        print_usage(&program, opts);
-        exit(EXIT_CODE_NO_OUTPUT);
-    }
+    let generate_config = matches.opt_present("generate-config");
+    let watch = matches.opt_present("watch");
 
     let should_verify_checksum = !matches.opt_present("b");
     let use_staging = matches.opt_present("s");
@@ -252,14 +371,9 @@ fn main() -> Result<()> {
         .unwrap_or(false);
     let secrets_enabled = secrets_enabled_old_option || secrets_enabled_new_option;
 
-    let output_file = matches
-        .opt_str("o")
-        .context("output file must be specified")?;
+    // Without an output file, the report is written to the standard output.
+    let output_file = matches.opt_str("o");
 
-    let mut path_config = PathConfig {
-        ignore: Vec::new(),
-        only: None,
-    };
     let ignore_paths_from_options = matches.opt_strs("p");
     let directory_to_analyze_option = matches.opt_str("i");
     let subdirectories_to_analyze = matches.opt_strs("u");
@@ -285,7 +399,13 @@ fn main() -> Result<()> {
         exit(EXIT_CODE_UNSAFE_SUBDIRECTORIES)
     }
 
-    let configuration_file_and_method = get_config(directory_to_analyze.as_str(), use_debug);
+    // `--generate-config` only uses the local configuration file, so that no network access is needed.
+    let configuration_file_and_method = if generate_config {
+        read_config_file(directory_to_analyze.as_str())
+            .map(|config_file| config_file.map(|cf| (cf, ConfigMethod::File)))
+    } else {
+        get_config(directory_to_analyze.as_str(), use_debug)
+    };
 
     let (configuration_file, configuration_method): (Option<ConfigFile>, Option<ConfigMethod>) =
         match configuration_file_and_method {
@@ -306,98 +426,6 @@ fn main() -> Result<()> {
         eprintln!("INFO: no configuration detected locally or remotely")
     }
 
-    let rule_config_provider = configuration_file
-        .as_ref()
-        .map(RuleConfigProvider::from_config)
-        .unwrap_or_default();
-    let mut rules: Vec<Rule> = Vec::new();
-
-    // if there is a configuration file, we load the rules from it. But it means
-    // we cannot have the rule parameter given.
-    if let Some(conf) = configuration_file {
-        ignore_gitignore = conf.ignore_gitignore.unwrap_or(false);
-        if rules_file.is_some() {
-            eprintln!("a rule file cannot be specified when a configuration file is present.");
-            exit(EXIT_CODE_RULE_FILE_WITH_CONFIGURATION);
-        }
-
-        if static_analysis_enabled {
-            let rulesets = conf.rulesets.keys().cloned().collect_vec();
-            let rules_from_api = get_rules_from_rulesets(&rulesets, use_staging, use_debug)
-                .inspect_err(|e| {
-                    if let DatadogApiError::RulesetNotFound(rs) = e {
-                        eprintln!("Error: ruleset {rs} not found");
-                        exit(EXIT_CODE_RULESET_NOT_FOUND);
-                    }
-                })
-                .context("error when reading rules from API")?;
-            rules.extend(rules_from_api);
-        }
-        // copy the only and ignore paths from the configuration file
-        path_config.ignore.extend(conf.paths.ignore);
-        path_config.only = conf.paths.only;
-
-        // Get the max file size from the configuration or default to the default constant.
-        max_file_size_kb = conf.max_file_size_kb.unwrap_or(DEFAULT_MAX_FILE_SIZE_KB);
-        ignore_generated_files = conf.ignore_generated_files.unwrap_or(true);
-    } else if static_analysis_enabled {
-        // if there is no config file, we take the default rules from our APIs.
-        if rules_file.is_none() {
-            println!("WARNING: no configuration file detected, getting the default rules from the Datadog API");
-            println!("Check the following resources to configure your rules:");
-            println!(
-                    " - Datadog documentation: https://docs.datadoghq.com/code_analysis/static_analysis"
-                );
-            println!(" - Static analyzer repository on GitHub: https://github.com/DataDog/datadog-static-analyzer");
-            let rulesets_from_api =
-                get_all_default_rulesets(use_staging, use_debug).expect("cannot get default rules");
-
-            rules.extend(rulesets_from_api.into_iter().flat_map(|rs| rs.into_rules()));
-        } else {
-            let rulesets_from_file = get_rulesets_from_file(rules_file.clone().unwrap().as_str());
-            rules.extend(
-                rulesets_from_file
-                    .context("cannot read ruleset from file")?
-                    .into_iter()
-                    .flat_map(|rs| rs.into_rules()),
-            );
-        }
-    }
-
-    let secrets_rules = if secrets_enabled {
-        get_secrets_rules(use_staging)?
-    } else {
-        vec![]
-    };
-
-    // add ignore path from the options
-    path_config
-        .ignore
-        .extend(ignore_paths_from_options.iter().map(|p| p.clone().into()));
-
-    // ignore all directories that are in gitignore
-    if !ignore_gitignore {
-        let paths_from_gitignore = read_files_from_gitignore(directory_to_analyze.as_str())
-            .expect("error when reading gitignore file");
-        path_config
-            .ignore
-            .extend(paths_from_gitignore.iter().map(|p| p.clone().into()));
-    }
-    if ignore_generated_files {
-        path_config
-            .ignore
-            .extend(DEFAULT_IGNORED_GLOBS.iter().map(|&p| p.to_string().into()));
-    }
-
-    let languages = get_languages_for_rules(&rules);
-
-    let files_in_repository = get_files(
-        directory_to_analyze.as_str(),
-        subdirectories_to_analyze.clone(),
-        &path_config,
-    )
-    .expect("unable to get the list of files to analyze");
-
     let num_cores_requested = matches
         .opt_str("c")
         .map(|val| {
@@ -405,33 +433,93 @@ fn main() -> Result<()> {
                 .context("unable to parse `cpus` flag as integer")
         })
         .transpose()?;
-    // Select the number of cores to use based on the user's CLI arg (or lack of one)
-    let num_cpus = choose_cpu_count(num_cores_requested);
+    let max_findings = matches
+        .opt_str("max-findings")
+        .map(|val| {
+            val.parse::<usize>()
+                .context("unable to parse `max-findings` flag as integer")
+        })
+        .transpose()?;
+    let sarif_level_mapping = matches
+        .opt_str("sarif-level-mapping")
+        .map(|val| {
+            parse_sarif_level_mapping(&val).context("unable to parse `sarif-level-mapping` flag")
+        })
+        .transpose()?
+        .unwrap_or_default();
+    let csv_columns = matches
+        .opt_str("csv-columns")
+        .map(|val| parse_csv_columns(&val).context("unable to parse `csv-columns` flag"))
+        .transpose()?
+        .unwrap_or_else(|| CsvColumn::ALL.to_vec());
 
-    // build the configuration object that contains how the CLI should behave.
-    let configuration = CliConfiguration {
+    let mut cli_args = ParsedCliArgs {
         use_debug,
-        configuration_method,
-        ignore_gitignore,
         source_directory: directory_to_analyze.clone(),
         source_subdirectories: subdirectories_to_analyze.clone(),
-        path_config,
         rules_file,
         output_format,
-        num_cpus,
-        rules,
-        rule_config_provider,
         output_file,
-        max_file_size_kb,
+        num_cpus: num_cores_requested,
+        ignore_paths: ignore_paths_from_options,
         use_staging,
         show_performance_statistics: enable_performance_statistics,
-        ignore_generated_files,
         static_analysis_enabled,
         secrets_enabled,
-        secrets_rules: secrets_rules.clone(),
+        max_findings,
+        rule_filter: RuleFilter::new(matches.opt_strs("exclude-rule"))
+            .with_included(matches.opt_strs("include-rule")),
     };
 
-    print_configuration(&configuration);
+    if generate_config {
+        // The rulesets come from the configuration file, so rules are only resolved when they
+        // are read from a local rules file (the default rulesets would be fetched from the API).
+        cli_args.static_analysis_enabled &= cli_args.rules_file.is_some();
+        cli_args.secrets_enabled = false;
+    }
+
+    // build the configuration object that contains how the CLI should behave.
+    let mut configuration =
+        match CliConfiguration::from_args_and_config(&cli_args, configuration_file.as_ref()) {
+            Ok(configuration) => configuration,
+            Err(CliConfigError::RuleFileWithConfiguration) => {
+                eprintln!("a rule file cannot be specified when a configuration file is present.");
+                exit(EXIT_CODE_RULE_FILE_WITH_CONFIGURATION);
+            }
+            Err(CliConfigError::RulesetNotFound(rs)) => {
+                eprintln!("Error: ruleset {rs} not found");
+                exit(EXIT_CODE_RULESET_NOT_FOUND);
+            }
+            Err(CliConfigError::Other(err)) => return Err(err),
+        };
+    configuration.configuration_method = configuration_method;
+
+    if generate_config {
+        let config_file = configuration.to_config_file(configuration_file.as_ref())?;
+        print!(
+            "{}",
+            config_file_to_yaml(&config_file).context("unable to generate the configuration")?
+        );
+        exit(0);
+    }
+
+    let languages = get_languages_for_rules(&configuration.rules);
+
+    let files_in_repository = get_files(
+        directory_to_analyze.as_str(),
+        subdirectories_to_analyze.clone(),
+        &configuration.path_config,
+    )
+    .expect("unable to get the list of files to analyze");
+
+    // When the report (or the `--watch` results) is written to the standard output, the progress
+    // messages go to stderr.
+    let mut status: Box<dyn Write> = if configuration.output_file.is_some() && !watch {
+        Box::new(io::stdout())
+    } else {
+        Box::new(io::stderr())
+    };
+    print_configuration(&mut status, &configuration)?;
 
     let mut all_rule_results = Vec::<RuleResult>::new();
     let mut all_stats = AnalysisStatistics::new();
@@ -446,11 +534,23 @@ fn main() -> Result<()> {
         })
         .transpose()?;
 
+    let rule_timeout_action = matches
+        .opt_str("rule-timeout-action")
+        .map(|val| {
+            RuleTimeoutAction::try_from(val.as_str())
+                .map_err(anyhow::Error::msg)
+                .context("unable to parse `rule-timeout-action` flag")
+        })
+        .transpose()?
+        .unwrap_or_default();
+
     let analysis_options = AnalysisOptions {
         log_output: true,
         use_debug,
-        ignore_generated_files,
+        ignore_generated_files: configuration.ignore_generated_files,
         timeout,
+        rule_timeout_action,
+        source_map_directory: matches.opt_str("source-map-directory").map(PathBuf::from),
     };
 
     // check if we do a diff-aware scan
@@ -458,27 +558,30 @@ fn main() -> Result<()> {
         match configuration.generate_diff_aware_request_data(configuration.use_debug) {
             Ok(params) => {
                 if configuration.use_debug {
-                    println!(
+                    writeln!(
+                        status,
                         "Diff-aware request with sha {}, branch {}, config hash {}",
                         params.sha, params.branch, params.config_hash
-                    );
+                    )?;
                 }
 
                 match get_diff_aware_information(&params, configuration.use_debug) {
                     Ok(d) => {
                         if configuration.use_debug {
-                            println!(
+                            writeln!(
+                                status,
                                 "diff aware enabled, base sha: {}, files to scan {}",
                                 d.base_sha,
                                 d.files.join(",")
-                            );
+                            )?;
                         } else {
-                            println!(
+                            writeln!(
+                                status,
                                 "diff-aware enabled, based sha {}, scanning only {}/{} files",
                                 d.base_sha,
                                 d.files.len(),
                                 files_in_repository.len()
-                            )
+                            )?;
                         }
                         Some(d)
                     }
@@ -510,7 +613,7 @@ fn main() -> Result<()> {
     };
 
     if configuration.use_debug {
-        println!("diff aware data: {:?}", diff_aware_parameters);
+        writeln!(status, "diff aware data: {:?}", diff_aware_parameters)?;
     }
 
     let num_threads = get_num_threads_to_use(&configuration);
@@ -538,19 +641,21 @@ fn main() -> Result<()> {
     };
 
     if configuration.use_debug && diff_aware_parameters.is_some() {
-        println!(
+        writeln!(
+            status,
             "{} files to scan with diff-aware: {}",
             files_to_analyze.len(),
             files_to_analyze
                 .iter()
                 .map(|x| x.as_os_str().to_str().unwrap().to_string())
                 .join(",")
-        );
+        )?;
     }
 
     // static analysis related variables used in other places
     let mut nb_violations: u32 = 0;
     let mut execution_time_secs: u64 = 0;
+    let mut findings_truncated = false;
     let mut all_path_metadata = HashMap::<String, ArtifactClassification>::new();
     if static_analysis_enabled {
         let mut all_path_metadata_static_analysis =
@@ -562,7 +667,22 @@ fn main() -> Result<()> {
             }
         }
 
+        if watch {
+            return watch_files(
+                &configuration,
+                &languages,
+                subdirectories_to_analyze,
+                &v8,
+                &analysis_options,
+            );
+        }
+
         let mut number_of_rules_used = 0;
+        // Set when a rule times out with `--rule-timeout-action abort`: no more files are analyzed.
+        let aborted = AtomicBool::new(false);
+        // The number of violations found across all languages, used to stop analyzing files once
+        // `--max-findings` is exceeded (the results are then truncated after each language).
+        let findings = AtomicUsize::new(0);
         // Finally run the analysis
         for language in &languages {
             let files_for_language = filter_files_for_language(&files_to_analyze, language);
@@ -585,19 +705,21 @@ fn main() -> Result<()> {
 
             number_of_rules_used += rules_for_language.len();
 
-            println!(
+            writeln!(
+                status,
                 "Analyzing {} {:?} files using {} rules",
                 files_for_language.len(),
                 language,
                 rules_for_language.len()
-            );
+            )?;
 
             if use_debug {
-                println!(
+                writeln!(
+                    status,
                     "Analyzing {}, {} files detected",
                     language,
                     files_for_language.len()
-                );
+                )?;
             }
 
             // take the relative path for the analysis
@@ -610,6 +732,12 @@ fn main() -> Result<()> {
                             // (`Cell` is used to allow lazy instantiation of a thread local with zero runtime cost).
                             static JS_RUNTIME: Cell<Option<JsRuntime>> = const { Cell::new(None) };
                         }
+                        let max_findings_exceeded = configuration
+                            .max_findings
+                            .is_some_and(|max| findings.load(Ordering::Relaxed) > max);
+                        if aborted.load(Ordering::Relaxed) || max_findings_exceeded {
+                            return (stats, fold_results, path_metadata);
+                        }
 
                         let relative_path = path
                             .strip_prefix(directory_path)
@@ -623,7 +751,13 @@ fn main() -> Result<()> {
                         let res = if let Ok(file_content) = read_file_with_fallback_encoding(&path) {
                             let mut opt = JS_RUNTIME.replace(None);
                             let runtime_ref = opt.get_or_insert_with(|| {
-                                v8.try_new_runtime().expect("ddsa init should succeed")
+                                let mut runtime =
+                                    v8.try_new_warm_runtime().expect("ddsa init should succeed");
+                                // Metrics reported by rules are only surfaced in debug mode.
+                                if use_debug {
+                                    runtime.set_metrics_sink(Box::new(PrintMetricsSink));
+                                }
+                                runtime
                             });
 
                             let file_content = Arc::from(file_content);
@@ -637,14 +771,18 @@ fn main() -> Result<()> {
                                 &analysis_options,
                             );
                             results.retain_mut(|r| {
-                                // We'll drop all `RuleResult` that don't contain violations
-                                let should_retain = !r.violations.is_empty();
+                                // We'll drop all `RuleResult` that don't contain violations (suppressed or not)
+                                let should_retain =
+                                    !r.violations.is_empty() || !r.suppressed_violations.is_empty();
 
                                 // Register the timings:
                                 // (The `RuleResult` vector for `errors` contains exactly 0 or 1 elements)
                                 if let Some(err) = r.errors.first() {
                                     if err == ERROR_RULE_TIMEOUT {
                                         stats.mark_timeout(&r.filename, &r.rule_name);
+                                        if rule_timeout_action == RuleTimeoutAction::Abort {
+                                            aborted.store(true, Ordering::Relaxed);
+                                        }
                                     } else {
                                         stats.mark_error(&r.filename, &r.rule_name);
                                     }
@@ -666,6 +804,8 @@ fn main() -> Result<()> {
 
                                 should_retain
                             });
+                            let violations = results.iter().map(|r| r.violations.len()).sum();
+                            findings.fetch_add(violations, Ordering::Relaxed);
 
                             if debug_java_dfa && *language == Language::Java {
                                 if let Some(graph) = generate_flow_graph_dot(
@@ -741,7 +881,26 @@ fn main() -> Result<()> {
             if let Some(pb) = &progress_bar {
                 pb.finish();
             }
+
+            // Stop the analysis once the maximum number of findings has been exceeded.
+            if let Some(max_findings) = configuration.max_findings {
+                if truncate_rule_results(&mut all_rule_results, max_findings) {
+                    findings_truncated = true;
+                    break;
+                }
+            }
+
+            if aborted.load(Ordering::Relaxed) {
+                eprintln!(
+                    "ERROR: a rule timed out, the analysis was aborted (--rule-timeout-action)"
+                );
+                break;
+            }
+        }
+        if let Some(source_map_directory) = &analysis_options.source_map_directory {
+            all_rule_results = translate_rule_results(all_rule_results, source_map_directory);
         }
+
         all_path_metadata = all_path_metadata_static_analysis
             .into_iter()
             .filter_map(|(k, v)| v.map(|classification| (k, classification)))
@@ -759,10 +918,17 @@ fn main() -> Result<()> {
 
         execution_time_secs = end_timestamp - start_timestamp;
 
-        println!(
+        writeln!(
+            status,
             "Found {} violation(s) in {} file(s) using {} rule(s) within {} sec(s)",
             nb_violations, total_files_analyzed, number_of_rules_used, execution_time_secs
-        );
+        )?;
+        if findings_truncated {
+            eprintln!(
+                "WARNING: results are truncated, only the first {} violation(s) are reported (--max-findings)",
+                nb_violations
+            );
+        }
     }
 
     // Secrets detection
@@ -780,9 +946,9 @@ fn main() -> Result<()> {
             None
         };
 
-        let sds_scanner = build_sds_scanner(&secrets_rules, use_debug);
+        let sds_scanner = build_sds_scanner(&configuration.secrets_rules, use_debug);
 
-        let nb_secrets_rules: usize = secrets_rules.len();
+        let nb_secrets_rules: usize = configuration.secrets_rules.len();
         let nb_secrets_files = secrets_files.len();
 
         secrets_results = secrets_files
@@ -797,7 +963,7 @@ fn main() -> Result<()> {
                     let file_content = Arc::from(file_content);
                     find_secrets(
                         &sds_scanner,
-                        &secrets_rules,
+                        &configuration.secrets_rules,
                         relative_path,
                         &file_content,
                         &analysis_options,
@@ -834,14 +1000,15 @@ fn main() -> Result<()> {
 
         let secrets_execution_time_secs = secrets_start.elapsed().as_secs();
 
-        println!(
+        writeln!(
+            status,
             "Found {} secret(s) (including {} valid) in {} file(s) using {} rule(s) within {} sec(s)",
             nb_secrets_found,
             nb_secrets_validated,
             nb_secrets_files,
             nb_secrets_rules,
             secrets_execution_time_secs
-        );
+        )?;
     }
 
     // If the performance statistics are enabled, we show the total execution time per rule
@@ -864,75 +1031,86 @@ fn main() -> Result<()> {
             ));
         }
 
-        println!("All rules execution time");
-        println!("------------------------");
+        writeln!(status, "All rules execution time")?;
+        writeln!(status, "------------------------")?;
         // Sort by total analysis time, descending
         analysis_times
             .sort_by_key(|&(_, query, execution, _)| std::cmp::Reverse(query + execution));
 
         for &(name, query, execution, count) in &analysis_times {
             let total_millis = (query + execution).as_millis();
-            println!(
+            writeln!(
+                status,
                 "rule {:?} total analysis time {:?} ms in {:?} files",
                 name, total_millis, count
-            );
+            )?;
         }
 
-        println!("Top 100 slowest rules breakdown");
-        println!("-------------------------------");
+        writeln!(status, "Top 100 slowest rules breakdown")?;
+        writeln!(status, "-------------------------------")?;
         // Show execution time breakdown in descending order.
         for &(name, query, execution, _) in analysis_times.iter().take(100) {
             let total = (query + execution).as_millis();
             let query = query.as_millis();
             let execution = execution.as_millis();
-            println!(
+            writeln!(
+                status,
                 "rule {:?}, total time {:?} ms, query node time {:?} ms, execution time {:?} ms",
                 name, total, query, execution
-            );
+            )?;
         }
 
-        println!("Top {} slowest files to parse", STATS_MAX_PARSE_TIMES);
-        println!("------------------------------");
+        writeln!(
+            status,
+            "Top {} slowest files to parse",
+            STATS_MAX_PARSE_TIMES
+        )?;
+        writeln!(status, "------------------------------")?;
         for (time, filename) in all_stats.file_parse_time.iter().rev() {
             let time = time.as_millis();
-            println!("file {:?}, parsing time {:?} ms", filename, time);
+            writeln!(status, "file {:?}, parsing time {:?} ms", filename, time)?;
         }
 
         // show the rules that timed out
-        println!("Rule timed out");
-        println!("--------------");
+        writeln!(status, "Rule timed out")?;
+        writeln!(status, "--------------")?;
         if all_stats.execution_timeouts.is_empty() {
-            println!("No rule timed out");
+            writeln!(status, "No rule timed out")?;
         }
         for (rule_name, files) in &all_stats.execution_timeouts {
             for filename in files {
-                println!("Rule {} timed out on file {}", rule_name, filename);
+                writeln!(status, "Rule {} timed out on file {}", rule_name, filename)?;
             }
         }
     }
 
     if print_violations && nb_violations > 0 {
-        violations_table::print_violations_table(&all_rule_results);
+        violations_table::print_violations_table(&mut status, &all_rule_results)?;
     }
 
     // if there is any violation at all and --fail-on-any-violation is passed, we exit 1
     let fail_on_violations = !fail_any_violation_severities.is_empty()
         && count_violations_by_severities(&all_rule_results, &fail_any_violation_severities) > 0;
 
-    let value = match configuration.output_format {
-        OutputFormat::Csv => csv::generate_csv_results(&all_rule_results, &secrets_results),
+    // write the reports
+    let sink: Box<dyn OutputSink> = match &configuration.output_file {
+        Some(output_file) => Box::new(FileSink(PathBuf::from(output_file))),
+        None => Box::new(StdoutSink),
+    };
+    match configuration.output_format {
+        OutputFormat::Csv => csv::write_csv_results(
+            sink.as_ref(),
+            &all_rule_results,
+            &secrets_results,
+            &csv_columns,
+        )
+        .context("error when writing results")?,
         OutputFormat::Json => {
-            let combined_results = [
-                secrets_results
-                    .iter()
-                    .map(convert_secret_result_to_rule_result)
-                    .collect(),
-                all_rule_results.clone(),
-            ]
-            .concat();
-            serde_json::to_string(&combined_results).expect("error when getting the JSON report")
+            json::write_json_results(sink.as_ref(), &all_rule_results, &secrets_results)
+                .context("error when writing results")?
         }
-        OutputFormat::Sarif => generate_sarif_file(
+        OutputFormat::Sarif => write_sarif_file(
+            sink.as_ref(),
             &configuration,
             all_rule_results,
             secrets_results,
@@ -942,17 +1120,13 @@ fn main() -> Result<()> {
                 config_digest: configuration.generate_diff_aware_digest(),
                 diff_aware_parameters,
                 execution_time_secs,
+                level_mapping: sarif_level_mapping,
+                findings_truncated,
             },
             &all_path_metadata,
-        )
-        .expect("cannot generate SARIF results"),
+        )?,
     };
 
-    // write the reports
-    let mut file = fs::File::create(configuration.output_file).context("cannot create file")?;
-    file.write_all(value.as_bytes())
-        .context("error when writing results")?;
-
     // if there is any violation at all and --fail-on-any-violation is passed, we exit 1
     if fail_on_violations {
         exit(EXIT_CODE_FAIL_ON_VIOLATION);
@@ -130,7 +130,7 @@ async fn analyze(
             let mut opt = JS_RUNTIME.replace(None);
             let runtime_ref = opt.get_or_insert_with(|| {
                 let v8 = V8_PLATFORM.get().expect("v8 should have been initialized");
-                v8.try_new_runtime().expect("ddsa init should succeed")
+                v8.try_new_warm_runtime().expect("ddsa init should succeed")
             });
             let request = request.into_inner();
             let (rule_responses, errors) =
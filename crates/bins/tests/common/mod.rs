@@ -0,0 +1,38 @@
+use std::fs;
+use std::path::PathBuf;
+
+/// A ruleset with a single Python rule that reports every identifier.
+pub const RULES: &str = r#"[
+  {
+    "name": "test-rules",
+    "description": null,
+    "rules": [
+      {
+        "name": "identifier",
+        "short_description": null,
+        "description": null,
+        "category": "BEST_PRACTICES",
+        "severity": "WARNING",
+        "language": "PYTHON",
+        "rule_type": "TREE_SITTER_QUERY",
+        "entity_checked": null,
+        "code": "ZnVuY3Rpb24gdmlzaXQocXVlcnksIGZpbGVuYW1lLCBjb2RlKSB7CiAgICBjb25zdCBuID0gcXVlcnkuY2FwdHVyZXNbIngiXTsKICAgIGFkZEVycm9yKGJ1aWxkRXJyb3Iobi5zdGFydC5saW5lLCBuLnN0YXJ0LmNvbCwgbi5lbmQubGluZSwgbi5lbmQuY29sLCAiaWRlbnRpZmllciIsICJXQVJOSU5HIiwgIkJFU1RfUFJBQ1RJQ0VTIikpOwp9",
+        "cwe": null,
+        "checksum": "",
+        "pattern": null,
+        "tree_sitter_query": "KGlkZW50aWZpZXIpIEB4",
+        "arguments": [],
+        "tests": [],
+        "is_testing": false
+      }
+    ]
+  }
+]"#;
+
+/// Creates an empty directory for the test in the system temporary directory.
+pub fn test_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("{}-{}", name, std::process::id()));
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&dir).unwrap();
+    dir
+}
@@ -0,0 +1,39 @@
+mod common;
+
+use common::{test_dir, RULES};
+use std::fs;
+use std::process::Command;
+
+/// Without `-o`, the report is the only thing written to stdout, even with the flags that print
+/// the configuration, debug messages, performance statistics and the violations table.
+#[test]
+fn report_is_the_only_output_on_stdout() {
+    let dir = test_dir("datadog-static-analyzer-stdout");
+    let repository = dir.join("repository");
+    fs::create_dir_all(&repository).unwrap();
+    fs::write(repository.join("main.py"), "foo = bar\n").unwrap();
+    let rules_file = dir.join("rules.json");
+    fs::write(&rules_file, RULES).unwrap();
+
+    let output = Command::new(env!("CARGO_BIN_EXE_datadog-static-analyzer"))
+        .arg("-i")
+        .arg(&repository)
+        .arg("-r")
+        .arg(&rules_file)
+        .args(["-b", "-f", "json", "-d", "yes", "-x", "--print-violations"])
+        .output()
+        .unwrap();
+    fs::remove_dir_all(&dir).unwrap();
+
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let results: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    let results = results.as_array().unwrap();
+    assert_eq!(results.len(), 1);
+    assert_eq!(results[0]["rule_name"], "test-rules/identifier");
+    assert_eq!(results[0]["violations"].as_array().unwrap().len(), 2);
+    assert!(!output.stderr.is_empty());
+}
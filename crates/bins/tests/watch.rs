@@ -0,0 +1,64 @@
+mod common;
+
+use common::{test_dir, RULES};
+use std::fs;
+use std::io::{BufRead, BufReader};
+use std::process::{Command, Stdio};
+use std::sync::mpsc;
+use std::thread;
+use std::time::Duration;
+
+/// With `--watch`, a file is analyzed when it is created, and its results are written to stdout as
+/// a line of JSON.
+#[test]
+fn watch_analyzes_changed_files() {
+    let dir = test_dir("datadog-static-analyzer-watch");
+    let repository = dir.join("repository");
+    fs::create_dir_all(&repository).unwrap();
+    let rules_file = dir.join("rules.json");
+    fs::write(&rules_file, RULES).unwrap();
+
+    let mut child = Command::new(env!("CARGO_BIN_EXE_datadog-static-analyzer"))
+        .arg("-i")
+        .arg(&repository)
+        .arg("-r")
+        .arg(&rules_file)
+        .args(["-b", "--watch"])
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .unwrap();
+
+    // Wait for the watcher to be ready before creating the file.
+    let (ready_tx, ready_rx) = mpsc::channel();
+    let stderr = BufReader::new(child.stderr.take().unwrap());
+    thread::spawn(move || {
+        for line in stderr.lines().map_while(Result::ok) {
+            if line.starts_with("Watching") {
+                let _ = ready_tx.send(());
+            }
+        }
+    });
+    let (line_tx, line_rx) = mpsc::channel();
+    let stdout = BufReader::new(child.stdout.take().unwrap());
+    thread::spawn(move || {
+        for line in stdout.lines().map_while(Result::ok) {
+            let _ = line_tx.send(line);
+        }
+    });
+    let timeout = Duration::from_secs(60);
+    ready_rx.recv_timeout(timeout).unwrap();
+    fs::write(repository.join("main.py"), "foo = bar\n").unwrap();
+    let line = line_rx.recv_timeout(timeout);
+
+    child.kill().unwrap();
+    child.wait().unwrap();
+    fs::remove_dir_all(&dir).unwrap();
+
+    let output: serde_json::Value = serde_json::from_str(&line.unwrap()).unwrap();
+    assert_eq!(output["filename"], "main.py");
+    let results = output["results"].as_array().unwrap();
+    assert_eq!(results.len(), 1);
+    assert_eq!(results[0]["rule_name"], "test-rules/identifier");
+    assert_eq!(results[0]["violations"].as_array().unwrap().len(), 2);
+}
@@ -1,66 +1,179 @@
+use crate::output::OutputSink;
+use anyhow::{anyhow, Result};
+use common::model::position::Position;
 use csv::Writer;
 use kernel::model::rule::{RuleCategory, RuleResult, RuleSeverity};
 use secrets::model::secret_result::SecretResult;
+use std::io;
+
+/// A column of the CSV output.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CsvColumn {
+    Filename,
+    Rule,
+    Category,
+    Severity,
+    Message,
+    StartLine,
+    StartCol,
+    EndLine,
+    EndCol,
+}
+
+impl CsvColumn {
+    /// All the columns, in the default order.
+    pub const ALL: [CsvColumn; 9] = [
+        Self::Filename,
+        Self::Rule,
+        Self::Category,
+        Self::Severity,
+        Self::Message,
+        Self::StartLine,
+        Self::StartCol,
+        Self::EndLine,
+        Self::EndCol,
+    ];
+
+    /// The name of the column, used in the header.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Filename => "filename",
+            Self::Rule => "rule",
+            Self::Category => "category",
+            Self::Severity => "severity",
+            Self::Message => "message",
+            Self::StartLine => "start_line",
+            Self::StartCol => "start_col",
+            Self::EndLine => "end_line",
+            Self::EndCol => "end_col",
+        }
+    }
+}
+
+impl TryFrom<&str> for CsvColumn {
+    type Error = anyhow::Error;
+
+    /// Parses a column from its name. The shorter `file`, `rule_name`, `line` and `col` are
+    /// accepted for `filename`, `rule`, `start_line` and `start_col`.
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        match value {
+            "file" => Ok(Self::Filename),
+            "rule_name" => Ok(Self::Rule),
+            "line" => Ok(Self::StartLine),
+            "col" => Ok(Self::StartCol),
+            _ => Self::ALL
+                .into_iter()
+                .find(|column| column.as_str() == value)
+                .ok_or_else(|| anyhow!("unknown CSV column `{value}`")),
+        }
+    }
+}
+
+/// Parses a comma-separated list of column names (e.g. `rule,filename,start_line`).
+pub fn parse_csv_columns(columns: &str) -> Result<Vec<CsvColumn>> {
+    columns
+        .split(',')
+        .map(|column| CsvColumn::try_from(column.trim()))
+        .collect()
+}
 
 pub fn generate_csv_results(
     rule_results: &Vec<RuleResult>,
     secrets_results: &[SecretResult],
+) -> String {
+    generate_csv_results_with_columns(rule_results, secrets_results, &CsvColumn::ALL)
+}
+
+/// Generates the CSV output with only the given columns, in the given order.
+pub fn generate_csv_results_with_columns(
+    rule_results: &[RuleResult],
+    secrets_results: &[SecretResult],
+    columns: &[CsvColumn],
 ) -> String {
     let mut wtr = Writer::from_writer(vec![]);
-    wtr.write_record([
-        "filename",
-        "rule",
-        "category",
-        "severity",
-        "message",
-        "start_line",
-        "start_col",
-        "end_line",
-        "end_col",
-    ])
-    .expect("csv serialization without issue");
+    wtr.write_record(columns.iter().map(CsvColumn::as_str))
+        .expect("csv serialization without issue");
 
     for r in rule_results {
         for v in &r.violations {
-            wtr.write_record(&[
-                r.filename.to_string(),
-                r.rule_name.to_string(),
-                v.category.to_string(),
-                v.severity.to_string(),
-                v.message.to_string(),
-                v.start.line.to_string(),
-                v.start.col.to_string(),
-                v.end.line.to_string(),
-                v.end.col.to_string(),
-            ])
-            .expect("csv serialization without issue for violation");
+            let record = build_record(
+                columns,
+                &r.filename,
+                &r.rule_name,
+                v.category,
+                v.severity,
+                &v.message,
+                v.start,
+                v.end,
+            );
+            wtr.write_record(&record)
+                .expect("csv serialization without issue for violation");
         }
     }
 
     for r in secrets_results {
         for v in &r.matches {
-            wtr.write_record(&[
-                r.filename.to_string(),
-                r.rule_name.to_string(),
-                RuleCategory::Security.to_string(),
-                RuleSeverity::Error.to_string(),
-                r.message.to_string(),
-                v.start.line.to_string(),
-                v.start.col.to_string(),
-                v.end.line.to_string(),
-                v.end.col.to_string(),
-            ])
-            .expect("csv serialization without issue for violation");
+            let record = build_record(
+                columns,
+                &r.filename,
+                &r.rule_name,
+                RuleCategory::Security,
+                RuleSeverity::Error,
+                &r.message,
+                v.start,
+                v.end,
+            );
+            wtr.write_record(&record)
+                .expect("csv serialization without issue for violation");
         }
     }
 
     String::from_utf8(wtr.into_inner().expect("generate CSV file")).expect("generate CSV file")
 }
 
+/// Writes the CSV output with only the given columns, in the given order, to `sink`.
+pub fn write_csv_results(
+    sink: &dyn OutputSink,
+    rule_results: &[RuleResult],
+    secrets_results: &[SecretResult],
+    columns: &[CsvColumn],
+) -> io::Result<()> {
+    let csv = generate_csv_results_with_columns(rule_results, secrets_results, columns);
+    sink.write(csv.as_bytes())
+}
+
+#[allow(clippy::too_many_arguments)]
+fn build_record(
+    columns: &[CsvColumn],
+    filename: &str,
+    rule_name: &str,
+    category: RuleCategory,
+    severity: RuleSeverity,
+    message: &str,
+    start: Position,
+    end: Position,
+) -> Vec<String> {
+    columns
+        .iter()
+        .map(|column| match column {
+            CsvColumn::Filename => filename.to_string(),
+            CsvColumn::Rule => rule_name.to_string(),
+            CsvColumn::Category => category.to_string(),
+            CsvColumn::Severity => severity.to_string(),
+            CsvColumn::Message => message.to_string(),
+            CsvColumn::StartLine => start.line.to_string(),
+            CsvColumn::StartCol => start.col.to_string(),
+            CsvColumn::EndLine => end.line.to_string(),
+            CsvColumn::EndCol => end.col.to_string(),
+        })
+        .collect()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    use crate::output::MemorySink;
     use kernel::model::rule::{RuleCategory, RuleSeverity};
     use kernel::model::violation::Violation;
 
@@ -84,7 +197,11 @@ mod tests {
                     category: RuleCategory::Performance,
                     fixes: vec![],
                     taint_flow: None,
+                    code_flows: vec![],
+                    related_locations: vec![],
                 }],
+                suppressed_violations: vec![],
+                custom_tags: vec![],
                 errors: vec![],
                 execution_error: None,
                 output: None,
@@ -96,4 +213,56 @@ mod tests {
         );
         assert_eq!(res_with_result, "filename,rule,category,severity,message,start_line,start_col,end_line,end_col\nfilename,myrule,performance,error,message,10,12,12,10\n");
     }
+
+    #[test]
+    fn test_export_csv_columns() {
+        let columns = parse_csv_columns("rule_name,file,line,severity,message").unwrap();
+        let res = generate_csv_results_with_columns(
+            &[RuleResult {
+                rule_name: "myrule".to_string(),
+                filename: "filename".to_string(),
+                violations: vec![Violation {
+                    start: common::model::position::Position { line: 10, col: 12 },
+                    end: common::model::position::Position { line: 12, col: 10 },
+                    message: "first, \"second\"\nthird".to_string(),
+                    severity: RuleSeverity::Error,
+                    category: RuleCategory::Performance,
+                    fixes: vec![],
+                    taint_flow: None,
+                    code_flows: vec![],
+                    related_locations: vec![],
+                }],
+                suppressed_violations: vec![],
+                custom_tags: vec![],
+                errors: vec![],
+                execution_error: None,
+                output: None,
+                execution_time_ms: 10,
+                query_node_time_ms: 0,
+                parsing_time_ms: 0,
+            }],
+            &[],
+            &columns,
+        );
+        assert_eq!(
+            res,
+            "rule,filename,start_line,severity,message\nmyrule,filename,10,error,\"first, \"\"second\"\"\nthird\"\n"
+        );
+    }
+
+    #[test]
+    fn test_write_csv_results() {
+        let sink = MemorySink::default();
+        write_csv_results(&sink, &[], &[], &[CsvColumn::Rule, CsvColumn::Filename]).unwrap();
+        assert_eq!(sink.0.into_inner(), b"rule,filename\n");
+    }
+
+    #[test]
+    fn test_parse_csv_columns() {
+        assert_eq!(
+            parse_csv_columns("filename, end_col").unwrap(),
+            vec![CsvColumn::Filename, CsvColumn::EndCol]
+        );
+        assert!(parse_csv_columns("filename,unknown").is_err());
+    }
 }
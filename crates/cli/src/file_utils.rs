@@ -96,9 +96,15 @@ pub fn read_files_from_gitignore_internal(path: &PathBuf) -> Result<Vec<String>>
     Ok(vec![])
 }
 
-pub fn read_files_from_gitignore(source_directory: &str) -> Result<Vec<String>> {
+/// Reads the `.gitignore` file in `source_directory` (if any) and returns a configuration that
+/// ignores the paths it excludes.
+pub fn read_path_config_from_gitignore(source_directory: &str) -> Result<PathConfig> {
     let gitignore_path = Path::new(source_directory).join(".gitignore");
-    read_files_from_gitignore_internal(&gitignore_path)
+    if !gitignore_path.exists() {
+        return Ok(PathConfig::default());
+    }
+    let content = read_to_string(gitignore_path)?;
+    Ok(PathConfig::from_gitignore_file(&content))
 }
 
 /// get the files to analyze from the directory. This function walks the directory
@@ -370,6 +376,27 @@ mod tests {
 
     use super::*;
 
+    #[test]
+    fn read_path_config_from_gitignore_patterns() {
+        let tempdir = tempfile::tempdir().unwrap();
+        let directory = tempdir.path().to_str().unwrap();
+        assert_eq!(
+            read_path_config_from_gitignore(directory).unwrap(),
+            PathConfig::default()
+        );
+
+        fs::write(
+            tempdir.path().join(".gitignore"),
+            "# comment\n/build\n*.so\n!keep.so\n",
+        )
+        .unwrap();
+        let path_config = read_path_config_from_gitignore(directory).unwrap();
+        assert_eq!(path_config.ignore.len(), 2);
+        assert!(!path_config.allows_file("build/out.py"));
+        assert!(path_config.allows_file("src/build/out.py"));
+        assert!(!path_config.allows_file("src/lib.so"));
+    }
+
     #[test]
     fn get_gitignore_exists() {
         let mut d = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
@@ -402,6 +429,8 @@ mod tests {
             category: RuleCategory::Performance,
             fixes: vec![],
             taint_flow: None,
+            code_flows: vec![],
+            related_locations: vec![],
         };
         let directory_string = d.into_os_string().into_string().unwrap();
         let fingerprint = get_fingerprint_for_violation(
@@ -446,6 +475,8 @@ mod tests {
             category: RuleCategory::Security,
             fixes: vec![],
             taint_flow: Some(vec![region0, region1]),
+            code_flows: vec![],
+            related_locations: vec![],
         };
         let fingerprint = get_fingerprint_for_violation(
             "taint_flow_rule".to_string(),
@@ -476,6 +507,8 @@ mod tests {
             category: RuleCategory::Performance,
             fixes: vec![],
             taint_flow: None,
+            code_flows: vec![],
+            related_locations: vec![],
         };
         let directory_string = d.into_os_string().into_string().unwrap();
 
@@ -542,7 +575,7 @@ mod tests {
             path_config: PathConfig::default(),
             rules_file: None,
             output_format: Sarif, // SARIF or JSON
-            output_file: "foo".to_string(),
+            output_file: Some("foo".to_string()),
             num_cpus: 2, // of cpus to use for parallelism
             rules: vec![],
             rule_config_provider: RuleConfigProvider::default(),
@@ -553,6 +586,7 @@ mod tests {
             secrets_enabled: false,
             static_analysis_enabled: true,
             secrets_rules: vec![],
+            max_findings: None,
         };
         assert_eq!(0, filter_files_by_size(&files1, &cli_configuration).len());
 
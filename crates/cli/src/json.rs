@@ -0,0 +1,43 @@
+use crate::output::OutputSink;
+use crate::rule_utils::convert_secret_result_to_rule_result;
+use kernel::model::rule::RuleResult;
+use secrets::model::secret_result::SecretResult;
+use std::io;
+
+/// Generates the JSON output: the secrets results (converted to rule results) followed by the
+/// static analysis results.
+pub fn generate_json_results(
+    rule_results: &[RuleResult],
+    secrets_results: &[SecretResult],
+) -> String {
+    let combined_results = secrets_results
+        .iter()
+        .map(convert_secret_result_to_rule_result)
+        .chain(rule_results.iter().cloned())
+        .collect::<Vec<_>>();
+    serde_json::to_string(&combined_results).expect("error when getting the JSON report")
+}
+
+/// Writes the JSON output to `sink`.
+pub fn write_json_results(
+    sink: &dyn OutputSink,
+    rule_results: &[RuleResult],
+    secrets_results: &[SecretResult],
+) -> io::Result<()> {
+    let json = generate_json_results(rule_results, secrets_results);
+    sink.write(json.as_bytes())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::output::MemorySink;
+
+    #[test]
+    fn test_write_json_results() {
+        let sink = MemorySink::default();
+        write_json_results(&sink, &[], &[]).unwrap();
+        assert_eq!(sink.0.into_inner(), b"[]");
+    }
+}
@@ -4,8 +4,12 @@ pub mod csv;
 pub mod datadog_utils;
 pub mod file_utils;
 pub mod git_utils;
+pub mod json;
 pub mod model;
+pub mod output;
 pub mod rule_utils;
 pub mod sarif;
+pub mod source_map;
 pub mod utils;
 pub mod violations_table;
+pub mod watch;
@@ -1,2 +1,3 @@
 pub mod cli_configuration;
 pub mod datadog_api;
+pub mod rule_filter;
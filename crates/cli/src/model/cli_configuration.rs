@@ -1,16 +1,62 @@
+use crate::constants::DEFAULT_MAX_FILE_SIZE_KB;
+use crate::datadog_utils::{
+    get_all_default_rulesets, get_rules_from_rulesets, get_secrets_rules, DatadogApiError,
+};
+use crate::file_utils::read_path_config_from_gitignore;
 use crate::git_utils::{get_branch, ORIGIN};
-use anyhow::anyhow;
+use crate::rule_utils::get_rulesets_from_file;
+use crate::utils::choose_cpu_count;
+use anyhow::{anyhow, Context};
 use common::model::diff_aware::DiffAware;
 use git2::Repository;
+use kernel::analysis::generated_content::DEFAULT_IGNORED_GLOBS;
 use kernel::model::common::OutputFormat;
-use kernel::model::config_file::{ConfigMethod, PathConfig};
+use kernel::model::config_file::{
+    CliOverrides, ConfigFile, ConfigMethod, PathConfig, PathPattern, RulesetConfig,
+};
 use kernel::rule_config::RuleConfigProvider;
 use sha2::{Digest, Sha256};
 
 use crate::model::datadog_api::DiffAwareRequestArguments;
+use crate::model::rule_filter::RuleFilter;
 use kernel::model::rule::Rule;
 use secrets::model::secret_rule::SecretRule;
 
+/// The command-line arguments used to build a [`CliConfiguration`].
+#[derive(Clone, Debug)]
+pub struct ParsedCliArgs {
+    pub use_debug: bool,
+    pub source_directory: String,
+    pub source_subdirectories: Vec<String>,
+    pub rules_file: Option<String>,
+    pub output_format: OutputFormat,
+    /// The file passed with `--output` (`None` writes the report to the standard output).
+    pub output_file: Option<String>,
+    /// The number of CPUs requested by the user, if any.
+    pub num_cpus: Option<usize>,
+    /// Globs passed with `--ignore-path`.
+    pub ignore_paths: Vec<String>,
+    pub use_staging: bool,
+    pub show_performance_statistics: bool,
+    pub static_analysis_enabled: bool,
+    pub secrets_enabled: bool,
+    /// The value of `--max-findings`, if any.
+    pub max_findings: Option<usize>,
+    /// The rules to include and exclude, from `--include-rule` and `--exclude-rule`.
+    pub rule_filter: RuleFilter,
+}
+
+/// An error encountered while building a [`CliConfiguration`].
+#[derive(Debug, thiserror::Error)]
+pub enum CliConfigError {
+    #[error("a rule file cannot be specified when a configuration file is present.")]
+    RuleFileWithConfiguration,
+    #[error("ruleset {0} not found")]
+    RulesetNotFound(String),
+    #[error(transparent)]
+    Other(#[from] anyhow::Error),
+}
+
 /// represents the CLI configuration
 #[derive(Clone)]
 pub struct CliConfiguration {
@@ -22,7 +68,8 @@ pub struct CliConfiguration {
     pub path_config: PathConfig,
     pub rules_file: Option<String>,
     pub output_format: OutputFormat, // SARIF or JSON
-    pub output_file: String,
+    /// The file the report is written to (`None` for the standard output).
+    pub output_file: Option<String>,
     pub num_cpus: usize, // of cpus to use for parallelism
     pub rules: Vec<Rule>,
     pub rule_config_provider: RuleConfigProvider,
@@ -33,6 +80,8 @@ pub struct CliConfiguration {
     pub static_analysis_enabled: bool,
     pub secrets_enabled: bool,
     pub secrets_rules: Vec<SecretRule>,
+    /// The maximum number of static analysis violations to report (`None` means no limit).
+    pub max_findings: Option<usize>,
 }
 
 impl DiffAware for CliConfiguration {
@@ -81,6 +130,151 @@ impl DiffAware for CliConfiguration {
 }
 
 impl CliConfiguration {
+    /// Builds the configuration from the command-line arguments and the (optional) configuration file.
+    ///
+    /// Paths from the configuration file are merged with the paths from the command-line and the
+    /// `.gitignore` file. An `--ignore-path` always takes precedence over the configuration file: it
+    /// excludes a path even if the configuration file lists it in `only`.
+    ///
+    /// Rules are loaded from the configuration file's rulesets, the rules file, or the default
+    /// rulesets (in that order of priority). The returned configuration has no `configuration_method`;
+    /// it is up to the caller to set it.
+    pub fn from_args_and_config(
+        args: &ParsedCliArgs,
+        config: Option<&ConfigFile>,
+    ) -> Result<Self, CliConfigError> {
+        let mut rules: Vec<Rule> = Vec::new();
+
+        // Apply the command-line overrides on top of the configuration file (or of an empty
+        // configuration when there is none).
+        let overrides = CliOverrides {
+            extra_ignore_paths: args.ignore_paths.iter().map(|p| p.clone().into()).collect(),
+            ..Default::default()
+        };
+        let effective_config = config
+            .cloned()
+            .unwrap_or_default()
+            .with_overrides(&overrides);
+        let mut path_config = effective_config.paths;
+        let ignore_gitignore = effective_config.ignore_gitignore.unwrap_or(false);
+        let max_file_size_kb = effective_config
+            .max_file_size_kb
+            .unwrap_or(DEFAULT_MAX_FILE_SIZE_KB);
+        let ignore_generated_files = effective_config.ignore_generated_files.unwrap_or(true);
+
+        let rule_config_provider = config
+            .map(RuleConfigProvider::from_config)
+            .unwrap_or_default();
+
+        // if there is a configuration file, we load the rules from it. But it means
+        // we cannot have the rule parameter given.
+        if let Some(conf) = config {
+            if args.rules_file.is_some() {
+                return Err(CliConfigError::RuleFileWithConfiguration);
+            }
+
+            if args.static_analysis_enabled {
+                let rulesets = conf.rulesets.keys().cloned().collect::<Vec<_>>();
+                let rules_from_api = get_rules_from_rulesets(
+                    &rulesets,
+                    args.use_staging,
+                    args.use_debug,
+                )
+                .map_err(|err| match err {
+                    DatadogApiError::RulesetNotFound(rs) => CliConfigError::RulesetNotFound(rs),
+                    err => anyhow::Error::new(err)
+                        .context("error when reading rules from API")
+                        .into(),
+                })?;
+                // A required ruleset that resolved to no rules at all is also an error.
+                let resolved_rulesets = rules_from_api
+                    .iter()
+                    .filter_map(|rule| rule.name.split_once('/').map(|(rs, _)| rs))
+                    .collect::<Vec<_>>();
+                if let Some(rs) = conf.missing_required_rulesets(&resolved_rulesets).first() {
+                    return Err(CliConfigError::RulesetNotFound(rs.to_string()));
+                }
+                rules.extend(rules_from_api);
+            }
+        } else if args.static_analysis_enabled {
+            // if there is no config file, we take the default rules from our APIs.
+            if let Some(rules_file) = &args.rules_file {
+                let rulesets_from_file = get_rulesets_from_file(rules_file.as_str());
+                rules.extend(
+                    rulesets_from_file
+                        .context("cannot read ruleset from file")?
+                        .into_iter()
+                        .flat_map(|rs| rs.into_rules()),
+                );
+            } else {
+                eprintln!("WARNING: no configuration file detected, getting the default rules from the Datadog API");
+                eprintln!("Check the following resources to configure your rules:");
+                eprintln!(
+                    " - Datadog documentation: https://docs.datadoghq.com/code_analysis/static_analysis"
+                );
+                eprintln!(" - Static analyzer repository on GitHub: https://github.com/DataDog/datadog-static-analyzer");
+                let rulesets_from_api = get_all_default_rulesets(args.use_staging, args.use_debug)
+                    .context("cannot get default rules")?;
+
+                rules.extend(rulesets_from_api.into_iter().flat_map(|rs| rs.into_rules()));
+            }
+        }
+
+        // remove the rules filtered out from the command-line
+        rules.retain(|rule| args.rule_filter.allows_rule(&rule.name));
+
+        let secrets_rules = if args.secrets_enabled {
+            get_secrets_rules(args.use_staging).context("cannot get secrets rules")?
+        } else {
+            vec![]
+        };
+
+        // ignore all directories that are in gitignore
+        if !ignore_gitignore {
+            let gitignore = read_path_config_from_gitignore(args.source_directory.as_str())
+                .context("error when reading gitignore file")?;
+            path_config.ignore.extend(gitignore.ignore);
+        }
+        if ignore_generated_files {
+            path_config
+                .ignore
+                .extend(DEFAULT_IGNORED_GLOBS.iter().map(|&p| p.to_string().into()));
+        }
+
+        Ok(CliConfiguration {
+            use_debug: args.use_debug,
+            configuration_method: None,
+            ignore_gitignore,
+            source_directory: args.source_directory.clone(),
+            source_subdirectories: args.source_subdirectories.clone(),
+            path_config,
+            rules_file: args.rules_file.clone(),
+            output_format: args.output_format.clone(),
+            output_file: args.output_file.clone(),
+            num_cpus: choose_cpu_count(args.num_cpus),
+            rules,
+            rule_config_provider,
+            max_file_size_kb,
+            use_staging: args.use_staging,
+            show_performance_statistics: args.show_performance_statistics,
+            ignore_generated_files,
+            static_analysis_enabled: args.static_analysis_enabled,
+            secrets_enabled: args.secrets_enabled,
+            secrets_rules,
+            max_findings: args.max_findings,
+        })
+    }
+
+    /// Returns the rules from `self.rules` that apply to the file at `path`, based on the path
+    /// restrictions of the configuration file.
+    pub fn active_rules_for_file(&self, path: &str) -> Vec<&Rule> {
+        let rule_config = self.rule_config_provider.config_for_file(path);
+        self.rules
+            .iter()
+            .filter(|rule| rule_config.rule_is_enabled(&rule.name))
+            .collect()
+    }
+
     /// Generate the diff-aware data from the configuration. It attempts to read
     /// the repository from the directory, get the repository information to get
     /// diff-aware data. If we are not in a repository or cannot get the data
@@ -120,7 +314,7 @@ impl CliConfiguration {
             }),
             _ => {
                 if self.use_debug {
-                    println!(
+                    eprintln!(
                         "config hash used to attempt to get diff-aware: {}",
                         config_hash
                     )
@@ -129,13 +323,67 @@ impl CliConfiguration {
             }
         }
     }
+
+    /// Converts the runtime configuration back to a [`ConfigFile`], so that command-line options
+    /// such as `--ignore-path` can be frozen into a configuration file.
+    ///
+    /// When `config` (the configuration file this configuration was built from) is provided, its
+    /// rulesets (including the per-rule configuration), required rulesets and global rule overrides
+    /// are carried over. Otherwise, the rulesets are derived from the names of the loaded rules.
+    /// User-specific fields (such as the source directory) are not included. Ignored paths that were
+    /// added automatically (from the `.gitignore` file or for generated files) are omitted, because
+    /// they are derived again from `ignore-gitignore` and `ignore-generated-files`.
+    ///
+    /// Fails if there is no ruleset to write, as a configuration file without rulesets is invalid.
+    pub fn to_config_file(&self, config: Option<&ConfigFile>) -> anyhow::Result<ConfigFile> {
+        let mut config_file = config.cloned().unwrap_or_else(|| ConfigFile {
+            rulesets: self
+                .rules
+                .iter()
+                .filter_map(|rule| rule.name.split_once('/').map(|(rs, _)| rs.to_string()))
+                .map(|rs| (rs, RulesetConfig::default()))
+                .collect(),
+            ..Default::default()
+        });
+        if config_file.rulesets.is_empty() {
+            return Err(anyhow!(
+                "no rulesets to write in the configuration: use a configuration file or a rules file"
+            ));
+        }
+
+        let mut implicit_ignores = Vec::<PathPattern>::new();
+        if !self.ignore_gitignore {
+            let gitignore =
+                read_path_config_from_gitignore(self.source_directory.as_str()).unwrap_or_default();
+            implicit_ignores.extend(gitignore.ignore);
+        }
+        if self.ignore_generated_files {
+            implicit_ignores.extend(DEFAULT_IGNORED_GLOBS.iter().map(|&p| p.to_string().into()));
+        }
+        config_file.paths = PathConfig {
+            only: self.path_config.only.clone(),
+            ignore: self
+                .path_config
+                .ignore
+                .iter()
+                .filter(|&p| !implicit_ignores.contains(p))
+                .cloned()
+                .collect(),
+        };
+        config_file.ignore_gitignore = Some(self.ignore_gitignore);
+        config_file.max_file_size_kb = Some(self.max_file_size_kb);
+        config_file.ignore_generated_files = Some(self.ignore_generated_files);
+        Ok(config_file)
+    }
 }
 
 #[cfg(test)]
 mod tests {
+    use kernel::config_file::{config_file_to_yaml, parse_config_file};
     use kernel::model::common::Language;
     use kernel::model::common::OutputFormat::Sarif;
     use kernel::model::rule::{RuleCategory, RuleSeverity, RuleType};
+    use kernel::model::ruleset::RuleSet;
 
     use super::*;
 
@@ -150,7 +398,7 @@ mod tests {
             path_config: PathConfig::default(),
             rules_file: None,
             output_format: Sarif, // SARIF or JSON
-            output_file: "foo".to_string(),
+            output_file: Some("foo".to_string()),
             num_cpus: 2, // of cpus to use for parallelism
             rules: vec![Rule {
                 name: "myrule".to_string(),
@@ -178,6 +426,7 @@ mod tests {
             secrets_enabled: false,
             static_analysis_enabled: true,
             secrets_rules: vec![],
+            max_findings: None,
         };
         assert_eq!(
             cli_configuration.generate_diff_aware_digest(),
@@ -185,6 +434,273 @@ mod tests {
         );
     }
 
+    fn parsed_cli_args(source_directory: &str) -> ParsedCliArgs {
+        ParsedCliArgs {
+            use_debug: false,
+            source_directory: source_directory.to_string(),
+            source_subdirectories: vec![],
+            rules_file: None,
+            output_format: Sarif,
+            output_file: Some("foo".to_string()),
+            num_cpus: Some(1),
+            ignore_paths: vec![],
+            use_staging: false,
+            show_performance_statistics: false,
+            // (Disabled so that no rules are fetched from the API)
+            static_analysis_enabled: false,
+            secrets_enabled: false,
+            max_findings: None,
+            rule_filter: RuleFilter::default(),
+        }
+    }
+
+    /// A shorthand function to create a Python rule with the given name.
+    fn rule_with_name(name: &str) -> Rule {
+        Rule {
+            name: name.to_string(),
+            short_description_base64: None,
+            description_base64: None,
+            category: RuleCategory::BestPractices,
+            severity: RuleSeverity::Warning,
+            language: Language::Python,
+            rule_type: RuleType::TreeSitterQuery,
+            entity_checked: None,
+            code_base64: "mycode".to_string(),
+            checksum: "foobar".to_string(),
+            pattern: None,
+            cwe: None,
+            tree_sitter_query_base64: None,
+            arguments: vec![],
+            tests: vec![],
+            is_testing: false,
+        }
+    }
+
+    /// Paths from `--ignore-path` are merged with the configuration file's paths, and they take
+    /// precedence over the configuration file's `only` paths.
+    #[test]
+    fn test_from_args_and_config_ignore_paths() {
+        let tempdir = tempfile::tempdir().unwrap();
+        let mut args = parsed_cli_args(tempdir.path().to_str().unwrap());
+        args.ignore_paths = vec!["src/legacy/**".to_string()];
+        let config = ConfigFile {
+            paths: PathConfig {
+                only: Some(vec!["src/**".to_string().into()]),
+                ignore: vec!["src/generated/**".to_string().into()],
+            },
+            ignore_gitignore: Some(true),
+            ignore_generated_files: Some(false),
+            max_file_size_kb: Some(42),
+            ..Default::default()
+        };
+
+        let cli_configuration =
+            CliConfiguration::from_args_and_config(&args, Some(&config)).unwrap();
+        let path_config = &cli_configuration.path_config;
+        assert_eq!(path_config.ignore.len(), 2);
+        assert!(path_config.allows_file("src/main.py"));
+        assert!(!path_config.allows_file("src/generated/main.py"));
+        assert!(!path_config.allows_file("src/legacy/main.py"));
+        assert!(!path_config.allows_file("tests/main.py"));
+        assert_eq!(cli_configuration.max_file_size_kb, 42);
+        assert!(cli_configuration.ignore_gitignore);
+        assert!(!cli_configuration.ignore_generated_files);
+
+        // Without a configuration file, only the CLI paths (and the default ignored globs) are used.
+        let cli_configuration = CliConfiguration::from_args_and_config(&args, None).unwrap();
+        let path_config = &cli_configuration.path_config;
+        assert!(path_config.only.is_none());
+        assert!(!path_config.allows_file("src/legacy/main.py"));
+        assert!(path_config.allows_file("tests/main.py"));
+        assert_eq!(cli_configuration.max_file_size_kb, DEFAULT_MAX_FILE_SIZE_KB);
+    }
+
+    /// `to_config_file` keeps the paths passed on the command line, but not the ones derived from
+    /// the `.gitignore` file or the generated files, and survives a round-trip through YAML.
+    #[test]
+    fn test_to_config_file_round_trip() {
+        let tempdir = tempfile::tempdir().unwrap();
+        std::fs::write(tempdir.path().join(".gitignore"), "build\n").unwrap();
+        let mut args = parsed_cli_args(tempdir.path().to_str().unwrap());
+        args.ignore_paths = vec!["src/legacy/**".to_string()];
+        let mut cli_configuration = CliConfiguration::from_args_and_config(&args, None).unwrap();
+        cli_configuration.rules = vec![
+            rule_with_name("python-security/rule1"),
+            rule_with_name("python-best-practices/rule2"),
+            rule_with_name("python-security/rule3"),
+        ];
+
+        let config = cli_configuration.to_config_file(None).unwrap();
+        assert_eq!(
+            config.rulesets.keys().collect::<Vec<_>>(),
+            vec!["python-security", "python-best-practices"]
+        );
+        assert_eq!(
+            config.paths.ignore,
+            vec![PathPattern::from("src/legacy/**".to_string())]
+        );
+        assert_eq!(config.ignore_gitignore, Some(false));
+        assert_eq!(config.ignore_generated_files, Some(true));
+        assert_eq!(config.max_file_size_kb, Some(DEFAULT_MAX_FILE_SIZE_KB));
+
+        let yaml = config_file_to_yaml(&config).unwrap();
+        assert_eq!(parse_config_file(&yaml).unwrap(), config);
+    }
+
+    /// `to_config_file` fails without a configuration file or rules, instead of generating a
+    /// configuration without rulesets that cannot be parsed.
+    #[test]
+    fn test_to_config_file_without_rules() {
+        let tempdir = tempfile::tempdir().unwrap();
+        let args = parsed_cli_args(tempdir.path().to_str().unwrap());
+        let cli_configuration = CliConfiguration::from_args_and_config(&args, None).unwrap();
+        assert!(cli_configuration.rules.is_empty());
+
+        let err = cli_configuration.to_config_file(None).unwrap_err();
+        assert!(err.to_string().contains("no rulesets"));
+    }
+
+    /// `to_config_file` carries over the rulesets, required rulesets and rule overrides of the
+    /// configuration file, and merges in the paths from the command line.
+    #[test]
+    fn test_to_config_file_from_config() {
+        let tempdir = tempfile::tempdir().unwrap();
+        let mut args = parsed_cli_args(tempdir.path().to_str().unwrap());
+        args.ignore_paths = vec!["src/legacy/**".to_string()];
+        let config = parse_config_file(
+            r#"
+schema-version: v1
+rulesets:
+  - python-security:
+    rules:
+      no-eval:
+        severity: ERROR
+        arguments:
+          max-depth: 3
+  - python-best-practices
+required-rulesets:
+  - python-security
+global-rule-overrides:
+  python-best-practices/no-print:
+    enabled: false
+ignore-paths:
+  - "src/generated/**"
+"#,
+        )
+        .unwrap();
+        let cli_configuration =
+            CliConfiguration::from_args_and_config(&args, Some(&config)).unwrap();
+
+        let generated = cli_configuration.to_config_file(Some(&config)).unwrap();
+        assert_eq!(generated.rulesets, config.rulesets);
+        assert_eq!(generated.required_rulesets, config.required_rulesets);
+        assert_eq!(
+            generated.global_rule_overrides,
+            config.global_rule_overrides
+        );
+        assert_eq!(
+            generated.paths.ignore,
+            vec![
+                PathPattern::from("src/generated/**".to_string()),
+                PathPattern::from("src/legacy/**".to_string())
+            ]
+        );
+
+        let yaml = config_file_to_yaml(&generated).unwrap();
+        assert_eq!(parse_config_file(&yaml).unwrap(), generated);
+    }
+
+    /// A rule restricted with `only` is not active for files outside of that path.
+    #[test]
+    fn test_active_rules_for_file() {
+        let tempdir = tempfile::tempdir().unwrap();
+        let args = parsed_cli_args(tempdir.path().to_str().unwrap());
+        let config = parse_config_file(
+            r#"
+rulesets:
+  - python-security:
+    only:
+      - "src/**"
+  - python-best-practices
+"#,
+        )
+        .unwrap();
+        let mut cli_configuration =
+            CliConfiguration::from_args_and_config(&args, Some(&config)).unwrap();
+        cli_configuration.rules = vec![
+            rule_with_name("python-security/no-eval"),
+            rule_with_name("python-best-practices/no-bare-except"),
+        ];
+
+        let names = |path: &str| {
+            cli_configuration
+                .active_rules_for_file(path)
+                .into_iter()
+                .map(|r| r.name.as_str())
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(
+            names("src/foo.py"),
+            vec![
+                "python-security/no-eval",
+                "python-best-practices/no-bare-except"
+            ]
+        );
+        assert_eq!(
+            names("tests/foo.py"),
+            vec!["python-best-practices/no-bare-except"]
+        );
+    }
+
+    /// Rules matching an `--exclude-rule` pattern are not used, while the other rules are.
+    #[test]
+    fn test_from_args_and_config_exclude_rule() {
+        let tempdir = tempfile::tempdir().unwrap();
+        let rulesets = vec![
+            RuleSet::new(
+                "python-security",
+                None,
+                vec![rule_with_name("no-eval"), rule_with_name("no-exec")],
+            ),
+            RuleSet::new(
+                "python-best-practices",
+                None,
+                vec![rule_with_name("no-eval")],
+            ),
+        ];
+        let rules_file = tempdir.path().join("rules.json");
+        std::fs::write(&rules_file, serde_json::to_string(&rulesets).unwrap()).unwrap();
+
+        let mut args = parsed_cli_args(tempdir.path().to_str().unwrap());
+        args.static_analysis_enabled = true;
+        args.rules_file = Some(rules_file.to_str().unwrap().to_string());
+        args.rule_filter = RuleFilter::new(vec![
+            "python-security/no-eval".to_string(),
+            "*/no-exec".to_string(),
+        ]);
+
+        let cli_configuration = CliConfiguration::from_args_and_config(&args, None).unwrap();
+        let rule_names = cli_configuration
+            .rules
+            .iter()
+            .map(|r| r.name.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(rule_names, vec!["python-best-practices/no-eval"]);
+    }
+
+    /// A rules file can't be combined with a configuration file.
+    #[test]
+    fn test_from_args_and_config_rules_file_with_config() {
+        let tempdir = tempfile::tempdir().unwrap();
+        let mut args = parsed_cli_args(tempdir.path().to_str().unwrap());
+        args.rules_file = Some("rules.json".to_string());
+        let res = CliConfiguration::from_args_and_config(&args, Some(&ConfigFile::default()));
+        assert!(matches!(
+            res,
+            Err(CliConfigError::RuleFileWithConfiguration)
+        ));
+    }
+
     #[test]
     fn test_generate_diff_aware_secret_rules_order_does_not_matter() {
         let secret_rule1 = SecretRule {
@@ -216,7 +732,7 @@ mod tests {
             path_config: PathConfig::default(),
             rules_file: None,
             output_format: Sarif, // SARIF or JSON
-            output_file: "foo".to_string(),
+            output_file: Some("foo".to_string()),
             num_cpus: 2, // of cpus to use for parallelism
             rules: vec![],
             rule_config_provider: Default::default(),
@@ -227,6 +743,7 @@ mod tests {
             secrets_enabled: false,
             static_analysis_enabled: true,
             secrets_rules: vec![],
+            max_findings: None,
         };
 
         let mut cli_configuration1 = cli_configuration_base.clone();
@@ -0,0 +1,146 @@
+/// Filters rules by name, based on the patterns passed on the command line.
+///
+/// Patterns have the form `<ruleset>/<rule>` and support `*` wildcards (e.g. `python-security/*`).
+/// When both included and excluded patterns are specified, a rule is only used if it matches an
+/// included pattern and doesn't match any excluded pattern.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct RuleFilter {
+    /// Patterns from `--include-rule`. If non-empty, only the rules matching one of these patterns are used.
+    included: Vec<String>,
+    /// Patterns from `--exclude-rule`. A rule matching any of these patterns is excluded.
+    excluded: Vec<String>,
+}
+
+impl RuleFilter {
+    /// Creates a new `RuleFilter` that excludes the rules matching any of the `excluded` patterns.
+    pub fn new(excluded: Vec<String>) -> Self {
+        Self {
+            included: Vec::new(),
+            excluded,
+        }
+    }
+
+    /// Restricts this filter to the rules matching any of the `included` patterns.
+    pub fn with_included(mut self, included: Vec<String>) -> Self {
+        self.included = included;
+        self
+    }
+
+    /// Returns `true` if this filter doesn't filter out any rule.
+    pub fn is_empty(&self) -> bool {
+        self.included.is_empty() && self.excluded.is_empty()
+    }
+
+    /// Returns `true` if the rule with the given name (`<ruleset>/<rule>`) should be used.
+    pub fn allows_rule(&self, rule_name: &str) -> bool {
+        let is_included = self.included.is_empty()
+            || self
+                .included
+                .iter()
+                .any(|pattern| wildcard_match(pattern, rule_name));
+        is_included
+            && !self
+                .excluded
+                .iter()
+                .any(|pattern| wildcard_match(pattern, rule_name))
+    }
+}
+
+/// Returns `true` if `text` matches `pattern`, where `*` in the pattern matches any
+/// sequence of characters (including an empty one).
+fn wildcard_match(pattern: &str, text: &str) -> bool {
+    let mut parts = pattern.split('*');
+    // `split` always yields at least one element.
+    let first = parts.next().unwrap_or_default();
+    let Some(mut remaining) = text.strip_prefix(first) else {
+        return false;
+    };
+    let mut parts = parts.collect::<Vec<_>>();
+    // If there is no wildcard, the pattern must match the text exactly.
+    let Some(last) = parts.pop() else {
+        return remaining.is_empty();
+    };
+    for part in parts {
+        match remaining.find(part) {
+            Some(idx) => remaining = &remaining[idx + part.len()..],
+            None => return false,
+        }
+    }
+    remaining.ends_with(last)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_wildcard_match() {
+        assert!(wildcard_match(
+            "python-security/no-eval",
+            "python-security/no-eval"
+        ));
+        assert!(!wildcard_match(
+            "python-security/no-eval",
+            "python-security/no-eval2"
+        ));
+        assert!(!wildcard_match(
+            "python-security/no-eval",
+            "python-security/no-ev"
+        ));
+        assert!(wildcard_match(
+            "python-security/*",
+            "python-security/no-eval"
+        ));
+        assert!(!wildcard_match(
+            "python-security/*",
+            "python-best-practices/no-eval"
+        ));
+        assert!(wildcard_match("*/no-eval", "python-security/no-eval"));
+        assert!(wildcard_match("python-*/no-*", "python-security/no-eval"));
+        assert!(!wildcard_match("python-*/no-*", "java-security/no-eval"));
+        assert!(wildcard_match("*", "python-security/no-eval"));
+        assert!(wildcard_match("a*a", "aa"));
+        assert!(!wildcard_match("a*a", "a"));
+    }
+
+    /// Multiple exclusion patterns are ORed.
+    #[test]
+    fn test_rule_filter_excluded() {
+        let filter = RuleFilter::new(vec![
+            "python-security/no-eval".to_string(),
+            "java-*/*".to_string(),
+        ]);
+        assert!(!filter.allows_rule("python-security/no-eval"));
+        assert!(!filter.allows_rule("java-security/sql-injection"));
+        assert!(!filter.allows_rule("java-best-practices/one-declaration"));
+        assert!(filter.allows_rule("python-security/no-exec"));
+        assert!(filter.allows_rule("python-best-practices/no-eval"));
+
+        assert!(RuleFilter::default().is_empty());
+        assert!(RuleFilter::default().allows_rule("python-security/no-eval"));
+    }
+
+    #[test]
+    fn test_rule_filter_included() {
+        let filter = RuleFilter::default().with_included(vec!["python-*".to_string()]);
+        assert!(!filter.is_empty());
+        assert!(filter.allows_rule("python-security/no-eval"));
+        assert!(filter.allows_rule("python-best-practices/no-bare-except"));
+        assert!(!filter.allows_rule("java-security/sql-injection"));
+        assert!(!filter.allows_rule("javascript-best-practices/no-console"));
+    }
+
+    /// When both are specified, a rule must be included and not excluded.
+    #[test]
+    fn test_rule_filter_included_and_excluded() {
+        let filter =
+            RuleFilter::new(vec!["python-security/no-eval".to_string()]).with_included(vec![
+                "python-security/*".to_string(),
+                "java-security/*".to_string(),
+            ]);
+        assert!(!filter.allows_rule("python-security/no-eval"));
+        assert!(filter.allows_rule("python-security/no-exec"));
+        assert!(filter.allows_rule("java-security/sql-injection"));
+        assert!(!filter.allows_rule("python-best-practices/no-bare-except"));
+    }
+}
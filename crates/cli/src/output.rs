@@ -0,0 +1,68 @@
+#[cfg(test)]
+use std::cell::RefCell;
+use std::fs;
+use std::io::{self, Write};
+use std::path::PathBuf;
+
+/// A destination for a serialized report.
+pub trait OutputSink {
+    fn write(&self, bytes: &[u8]) -> io::Result<()>;
+}
+
+/// Writes the report to the standard output.
+pub struct StdoutSink;
+
+impl OutputSink for StdoutSink {
+    fn write(&self, bytes: &[u8]) -> io::Result<()> {
+        let mut stdout = io::stdout().lock();
+        stdout.write_all(bytes)?;
+        stdout.flush()
+    }
+}
+
+/// Writes the report to a file, replacing any existing content.
+pub struct FileSink(pub PathBuf);
+
+impl OutputSink for FileSink {
+    fn write(&self, bytes: &[u8]) -> io::Result<()> {
+        fs::write(&self.0, bytes)
+    }
+}
+
+/// Keeps the report in memory, so that the serializers can be tested without the filesystem.
+#[cfg(test)]
+#[derive(Default)]
+pub(crate) struct MemorySink(pub(crate) RefCell<Vec<u8>>);
+
+#[cfg(test)]
+impl OutputSink for MemorySink {
+    fn write(&self, bytes: &[u8]) -> io::Result<()> {
+        self.0.borrow_mut().extend_from_slice(bytes);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_file_sink() {
+        let tempdir = tempfile::tempdir().unwrap();
+        let path = tempdir.path().join("results.json");
+        fs::write(&path, "previous content").unwrap();
+
+        let sink: &dyn OutputSink = &FileSink(path.clone());
+        sink.write(b"[]").unwrap();
+        assert_eq!(fs::read_to_string(&path).unwrap(), "[]");
+
+        let sink = FileSink(tempdir.path().join("missing/results.json"));
+        assert!(sink.write(b"[]").is_err());
+    }
+
+    #[test]
+    fn test_stdout_sink() {
+        let sink: &dyn OutputSink = &StdoutSink;
+        assert!(sink.write(b"").is_ok());
+    }
+}
@@ -40,6 +40,36 @@ pub fn count_violations_by_severities(
         .sum()
 }
 
+/// Caps the total number of violations in `rule_results` to `max_findings`.
+///
+/// Results are first sorted by filename and rule name so that the violations kept do not depend
+/// on the order in which files were analyzed. Results left without any violation by the truncation
+/// are removed, unless they also carry suppressed violations or errors.
+/// Returns `true` if any violation was dropped.
+pub fn truncate_rule_results(rule_results: &mut Vec<RuleResult>, max_findings: usize) -> bool {
+    let total: usize = rule_results.iter().map(|r| r.violations.len()).sum();
+    if total <= max_findings {
+        return false;
+    }
+    rule_results.sort_by(|a, b| {
+        a.filename
+            .cmp(&b.filename)
+            .then_with(|| a.rule_name.cmp(&b.rule_name))
+    });
+    let mut remaining = max_findings;
+    rule_results.retain_mut(|result| {
+        let had_violations = !result.violations.is_empty();
+        result.violations.truncate(remaining);
+        remaining -= result.violations.len();
+        !had_violations
+            || !result.violations.is_empty()
+            || !result.suppressed_violations.is_empty()
+            || !result.errors.is_empty()
+            || result.execution_error.is_some()
+    });
+    true
+}
+
 /// Transform a secret result into a rule result, which is required for output purposes.
 pub fn convert_secret_result_to_rule_result(secret_result: &SecretResult) -> RuleResult {
     RuleResult {
@@ -62,8 +92,12 @@ pub fn convert_secret_result_to_rule_result(secret_result: &SecretResult) -> Rul
                 category: RuleCategory::Security,
                 fixes: vec![],
                 taint_flow: None,
+                code_flows: vec![],
+                related_locations: vec![],
             })
             .collect(),
+        suppressed_violations: vec![],
+        custom_tags: vec![],
     }
 }
 
@@ -87,7 +121,7 @@ pub fn convert_rules_to_rules_internal(
                 .context(format!("cannot convert {} to rule internal", r.name));
 
             if configuration.show_performance_statistics {
-                println!(
+                eprintln!(
                     "Rule {} conversion to rule internal: {} ms",
                     r.name,
                     rule_conversion_time.elapsed().as_millis()
@@ -99,7 +133,7 @@ pub fn convert_rules_to_rules_internal(
         .collect::<anyhow::Result<Vec<_>>>();
 
     if configuration.show_performance_statistics {
-        println!(
+        eprintln!(
             "Total time to convert rules to rules internal for language {}: {} ms",
             language,
             rules_conversion_time.elapsed().as_millis()
@@ -120,12 +154,19 @@ pub fn check_rules_checksum(rules: &[Rule]) -> anyhow::Result<()> {
 
 #[cfg(test)]
 mod tests {
+    use common::analysis_options::AnalysisOptions;
     use common::model::position::Position;
+    use kernel::analysis::analyze::analyze_with;
+    use kernel::analysis::ddsa_lib::test_utils::cfg_test_v8;
+    use kernel::analysis::tree_sitter::get_query;
     use kernel::model::violation::Violation;
     use kernel::model::{
         common::Language,
         rule::{RuleCategory, RuleSeverity, RuleType},
     };
+    use kernel::rule_config::RuleConfig;
+    use std::path::Path;
+    use std::sync::Arc;
 
     use super::*;
 
@@ -143,6 +184,8 @@ mod tests {
                     category: RuleCategory::Performance,
                     fixes: vec![],
                     taint_flow: None,
+                    code_flows: vec![],
+                    related_locations: vec![],
                 },
                 Violation {
                     start: Position { line: 10, col: 12 },
@@ -152,6 +195,8 @@ mod tests {
                     category: RuleCategory::Performance,
                     fixes: vec![],
                     taint_flow: None,
+                    code_flows: vec![],
+                    related_locations: vec![],
                 },
                 Violation {
                     start: Position { line: 10, col: 12 },
@@ -161,8 +206,12 @@ mod tests {
                     category: RuleCategory::Performance,
                     fixes: vec![],
                     taint_flow: None,
+                    code_flows: vec![],
+                    related_locations: vec![],
                 },
             ],
+            suppressed_violations: vec![],
+            custom_tags: vec![],
             errors: vec![],
             execution_error: None,
             output: None,
@@ -189,6 +238,150 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_truncate_rule_results() {
+        let rule_result = |filename: &str, rule_name: &str, count: usize| RuleResult {
+            rule_name: rule_name.to_string(),
+            filename: filename.to_string(),
+            violations: (0..count as u32)
+                .map(|line| Violation {
+                    start: Position { line, col: 1 },
+                    end: Position { line, col: 2 },
+                    message: "message".to_string(),
+                    severity: RuleSeverity::Error,
+                    category: RuleCategory::Performance,
+                    fixes: vec![],
+                    taint_flow: None,
+                    code_flows: vec![],
+                    related_locations: vec![],
+                })
+                .collect(),
+            suppressed_violations: vec![],
+            custom_tags: vec![],
+            errors: vec![],
+            execution_error: None,
+            output: None,
+            execution_time_ms: 0,
+            parsing_time_ms: 0,
+            query_node_time_ms: 0,
+        };
+        let all_results = vec![
+            rule_result("c.py", "rule1", 50),
+            rule_result("a.py", "rule2", 40),
+            rule_result("a.py", "rule1", 30),
+            rule_result("b.py", "rule1", 20),
+        ];
+        let total =
+            |results: &[RuleResult]| -> usize { results.iter().map(|r| r.violations.len()).sum() };
+
+        // Under the limit: nothing is dropped.
+        let mut results = all_results.clone();
+        assert!(!truncate_rule_results(&mut results, 140));
+        assert_eq!(total(&results), 140);
+
+        // Over the limit: the count is capped, and the results are kept in a deterministic order.
+        let mut results = all_results.clone();
+        assert!(truncate_rule_results(&mut results, 75));
+        assert_eq!(total(&results), 75);
+        let kept = results
+            .iter()
+            .map(|r| {
+                (
+                    r.filename.as_str(),
+                    r.rule_name.as_str(),
+                    r.violations.len(),
+                )
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            kept,
+            vec![
+                ("a.py", "rule1", 30),
+                ("a.py", "rule2", 40),
+                ("b.py", "rule1", 5)
+            ]
+        );
+
+        let mut results = all_results;
+        assert!(truncate_rule_results(&mut results, 0));
+        assert!(results.is_empty());
+    }
+
+    /// Truncating the results of a scanned fixture caps its violations, but keeps the results
+    /// that report an error.
+    #[test]
+    fn test_truncate_scanned_fixture() {
+        let rule = |name: &str, query: &str, code: &str| RuleInternal {
+            name: name.to_string(),
+            short_description: None,
+            description: None,
+            category: RuleCategory::BestPractices,
+            severity: RuleSeverity::Warning,
+            language: Language::Python,
+            code: code.to_string(),
+            tree_sitter_query: Some(get_query(query, &Language::Python).unwrap()),
+        };
+        let rules = [
+            rule(
+                "python/call",
+                "(call) @call",
+                r#"
+function visit(captures) {
+    const node = captures.get("call");
+    addError(buildError(node.start.line, node.start.col, node.end.line, node.end.col, "call"));
+}
+"#,
+            ),
+            rule(
+                "python/throws",
+                "(call) @call",
+                r#"
+function visit(captures) {
+    throw new Error("rule failure");
+}
+"#,
+            ),
+        ];
+
+        let fixture = Path::new("resources/test/max_findings");
+        let mut runtime = cfg_test_v8().new_runtime();
+        let mut results = Vec::new();
+        for filename in ["a.py", "b.py"] {
+            let code = std::fs::read_to_string(fixture.join(filename)).unwrap();
+            results.extend(analyze_with(
+                &mut runtime,
+                &Language::Python,
+                &rules,
+                &Arc::from(filename),
+                &Arc::from(code),
+                &RuleConfig::default(),
+                &AnalysisOptions::default(),
+            ));
+        }
+        assert_eq!(results.iter().map(|r| r.violations.len()).sum::<usize>(), 4);
+
+        assert!(truncate_rule_results(&mut results, 2));
+        let kept = results
+            .iter()
+            .map(|r| {
+                (
+                    r.filename.as_str(),
+                    r.rule_name.as_str(),
+                    r.violations.len(),
+                    r.execution_error.is_some(),
+                )
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            kept,
+            vec![
+                ("a.py", "python/call", 2, false),
+                ("a.py", "python/throws", 0, true),
+                ("b.py", "python/throws", 0, true),
+            ]
+        );
+    }
+
     // make sure we correctly get rulesets from a string
     #[test]
     fn parse_rulesets_from_string() {
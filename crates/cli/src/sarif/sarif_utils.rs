@@ -3,7 +3,7 @@ use std::path::Path;
 use std::rc::Rc;
 
 use crate::constants::{SARIF_PROPERTY_DATADOG_FINGERPRINT, SARIF_PROPERTY_SHA};
-use anyhow::Result;
+use anyhow::{anyhow, Context, Result};
 use base64::Engine;
 use common::model::position::Position;
 use common::model::position::PositionBuilder;
@@ -11,7 +11,7 @@ use git2::{BlameOptions, Repository};
 use kernel::classifiers::ArtifactClassification;
 use kernel::constants::CARGO_VERSION;
 use kernel::model::rule::{RuleCategory, RuleSeverity};
-use kernel::model::violation::Violation;
+use kernel::model::violation::{CodeFlow, Violation};
 use kernel::model::{
     rule::{Rule, RuleResult},
     violation::{Edit, EditType},
@@ -24,13 +24,14 @@ use serde_sarif::sarif::{
     self, Artifact, ArtifactBuilder, ArtifactChangeBuilder, ArtifactLocationBuilder, FixBuilder,
     LocationBuilder, MessageBuilder, PhysicalLocationBuilder, PropertyBagBuilder, RegionBuilder,
     Replacement, ReportingDescriptor, Result as SarifResult, ResultBuilder, RunBuilder, Sarif,
-    SarifBuilder, Tool, ToolBuilder, ToolComponent, ToolComponentBuilder,
+    SarifBuilder, SuppressionBuilder, Tool, ToolBuilder, ToolComponent, ToolComponentBuilder,
 };
 
 use crate::file_utils::get_fingerprint_for_violation;
 use crate::model::cli_configuration::CliConfiguration;
 use crate::model::datadog_api::DiffAwareData;
-use crate::sarif::sarif_utils::SarifViolation::{Secret, StaticAnalysis};
+use crate::output::OutputSink;
+use crate::sarif::sarif_utils::SarifViolation::{Secret, StaticAnalysis, Suppressed};
 
 trait IntoSarif {
     type SarifType;
@@ -47,6 +48,10 @@ pub struct SarifReportMetadata {
     pub config_digest: String,
     pub diff_aware_parameters: Option<DiffAwareData>,
     pub execution_time_secs: u64,
+    /// Overrides for the default mapping from a rule severity to a SARIF level.
+    pub level_mapping: HashMap<RuleSeverity, SarifLevel>,
+    /// Whether violations were dropped because of `--max-findings`.
+    pub findings_truncated: bool,
 }
 
 #[derive(Debug, Clone)]
@@ -125,6 +130,8 @@ impl From<SecretRule> for SarifRule {
 pub enum SarifViolation {
     StaticAnalysis(Violation),
     Secret(Violation, SecretValidationStatus),
+    /// A static analysis violation suppressed by a comment, along with the line of that comment.
+    Suppressed(Violation, u32),
 }
 
 impl SarifViolation {
@@ -132,6 +139,20 @@ impl SarifViolation {
         match self {
             StaticAnalysis(v) => v,
             Secret(v, _) => v,
+            Suppressed(v, _) => v,
+        }
+    }
+
+    /// Returns the SARIF suppressions for this violation (an empty list if it's not suppressed).
+    fn get_suppressions(&self) -> Result<Vec<sarif::Suppression>> {
+        if let Suppressed(_, comment_line) = &self {
+            let suppression = SuppressionBuilder::default()
+                .kind("inSource")
+                .justification(format!("datadog-disable comment at line {}", comment_line))
+                .build()?;
+            Ok(vec![suppression])
+        } else {
+            Ok(vec![])
         }
     }
 
@@ -164,6 +185,11 @@ impl SarifRuleResult {
                 .violations
                 .iter()
                 .map(|v| StaticAnalysis(v.clone()))
+                .chain(
+                    r.suppressed_violations
+                        .iter()
+                        .map(|s| Suppressed(s.violation.clone(), s.comment_line)),
+                )
                 .collect::<Vec<SarifViolation>>(),
             SarifRuleResult::Secret(secret_result) => secret_result
                 .matches
@@ -186,6 +212,8 @@ impl SarifRuleResult {
                             category: RuleCategory::Security,
                             fixes: vec![],
                             taint_flow: None,
+                            code_flows: vec![],
+                            related_locations: vec![],
                         },
                         r.validation_status,
                     )
@@ -210,6 +238,14 @@ impl SarifRuleResult {
         })
     }
 
+    /// Returns the user-defined tags from the rule's configuration (always empty for secrets).
+    fn custom_tags(&self) -> &[String] {
+        match self {
+            SarifRuleResult::StaticAnalysis(r) => &r.custom_tags,
+            SarifRuleResult::Secret(_) => &[],
+        }
+    }
+
     fn rule_name(&self) -> &str {
         match self {
             SarifRuleResult::StaticAnalysis(r) => r.rule_name.as_str(),
@@ -261,6 +297,8 @@ pub struct SarifGenerationOptions {
     pub diff_aware_parameters: Option<DiffAwareData>,
     pub repository_directory: String,
     pub execution_time_secs: u64,
+    pub level_mapping: HashMap<RuleSeverity, SarifLevel>,
+    pub findings_truncated: bool,
 }
 
 impl IntoSarif for &SecretRule {
@@ -451,6 +489,11 @@ fn generate_tool_section(rules: &[SarifRule], options: &SarifGenerationOptions)
         tags.push("DATADOG_DIFF_AWARE_ENABLED:false".to_string());
     }
 
+    // if violations were dropped, the report is incomplete
+    if options.findings_truncated {
+        tags.push("DATADOG_RESULTS_TRUNCATED:true".to_string());
+    }
+
     let driver: ToolComponent = ToolComponentBuilder::default()
         .name("datadog-static-analyzer")
         .version(CARGO_VERSION)
@@ -485,18 +528,83 @@ fn is_valid_violation(violation: &Violation) -> bool {
     })
 }
 
+/// A SARIF `level` for a result.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum SarifLevel {
+    None,
+    Note,
+    Warning,
+    Error,
+}
+
+impl SarifLevel {
+    fn as_str(&self) -> &'static str {
+        match self {
+            SarifLevel::None => "none",
+            SarifLevel::Note => "note",
+            SarifLevel::Warning => "warning",
+            SarifLevel::Error => "error",
+        }
+    }
+}
+
+impl TryFrom<&str> for SarifLevel {
+    type Error = &'static str;
+
+    fn try_from(s: &str) -> Result<Self, &'static str> {
+        match s.to_lowercase().as_str() {
+            "none" => Ok(SarifLevel::None),
+            "note" => Ok(SarifLevel::Note),
+            "warning" => Ok(SarifLevel::Warning),
+            "error" => Ok(SarifLevel::Error),
+            _ => Err("unknown SARIF level"),
+        }
+    }
+}
+
+/// Parses a JSON object mapping rule severities to SARIF levels, for example
+/// `{"Warning":"note","Error":"error"}`. Both keys and values are case-insensitive.
+pub fn parse_sarif_level_mapping(json: &str) -> Result<HashMap<RuleSeverity, SarifLevel>> {
+    let raw: HashMap<String, String> = serde_json::from_str(json)
+        .map_err(|e| anyhow!("the SARIF level mapping must be a JSON object of strings: {e}"))?;
+    raw.into_iter()
+        .map(|(severity, level)| {
+            let severity = RuleSeverity::try_from(severity.as_str()).map_err(|_| {
+                anyhow!(
+                    "unknown severity `{severity}` (expected one of: error, warning, notice, none)"
+                )
+            })?;
+            let level = SarifLevel::try_from(level.as_str()).map_err(|_| {
+                anyhow!(
+                    "unknown SARIF level `{level}` (expected one of: none, note, warning, error)"
+                )
+            })?;
+            Ok((severity, level))
+        })
+        .collect()
+}
+
 /// Convert our severity enumeration into the corresponding SARIF values.
 /// The main discrepancy here is that Notice maps to note.
 /// See [this document](https://github.com/oasis-tcs/sarif-spec/blob/main/Documents/CommitteeSpecifications/2.1.0/sarif-schema-2.1.0.json#L1566)
 /// for the full SARIF standard.
-fn get_level_from_severity(severity: RuleSeverity) -> String {
-    match severity {
-        RuleSeverity::Notice => "note",
-        RuleSeverity::Warning => "warning",
-        RuleSeverity::Error => "error",
-        _ => "none",
-    }
-    .to_string()
+///
+/// If `level_mapping` contains an override for the severity, that level is used instead.
+fn get_level_from_severity(
+    severity: RuleSeverity,
+    level_mapping: &HashMap<RuleSeverity, SarifLevel>,
+) -> String {
+    let default = match severity {
+        RuleSeverity::Notice => SarifLevel::Note,
+        RuleSeverity::Warning => SarifLevel::Warning,
+        RuleSeverity::Error => SarifLevel::Error,
+        RuleSeverity::None => SarifLevel::None,
+    };
+    level_mapping
+        .get(&severity)
+        .unwrap_or(&default)
+        .as_str()
+        .to_string()
 }
 
 /// Get the latest commit id/sha for a file/line. This is done to know the latest SHA for a line with
@@ -595,7 +703,10 @@ fn generate_results(
 
                 result_builder.rule_index(i64::try_from(rule_index).unwrap());
 
-                result_builder.level(get_level_from_severity(rule.severity()));
+                result_builder.level(get_level_from_severity(
+                    rule.severity(),
+                    &options_orig.level_mapping,
+                ));
                 tags.push(category);
 
                 // If there is a CWE, add it
@@ -608,6 +719,7 @@ fn generate_results(
                     tags.push("DATADOG_TESTING:true".to_string());
                 }
             }
+            tags.extend(rule_result.custom_tags().iter().cloned());
 
             let options = options_orig.clone();
             let violations = rule_result.violations();
@@ -715,6 +827,29 @@ fn generate_results(
                         .transpose();
                     let taint_code_flow = taint_code_flow?;
 
+                    let rule_code_flows = violation
+                        .code_flows
+                        .iter()
+                        .filter(|code_flow| !code_flow.is_empty())
+                        .map(generate_code_flow)
+                        .collect::<Result<Vec<_>>>()?;
+
+                    let related_locations = violation
+                        .related_locations
+                        .iter()
+                        .enumerate()
+                        .map(|(idx, related)| {
+                            let mut location = generate_location(
+                                &related.file,
+                                related.line,
+                                related.col,
+                                &related.message,
+                            )?;
+                            location.id = Some(i64::try_from(idx)?);
+                            Ok(location)
+                        })
+                        .collect::<Result<Vec<_>>>()?;
+
                     let sha_option = if options.add_git_info {
                         get_sha_for_line(
                             &rule_result.slash_path_str(),
@@ -755,7 +890,10 @@ fn generate_results(
                     // status. We override it here. For static analysis, we report the
                     // severity of the rule before.
                     if let SarifRuleResult::Secret(_) = rule_result {
-                        sarif_result.level(get_level_from_severity(violation.severity));
+                        sarif_result.level(get_level_from_severity(
+                            violation.severity,
+                            &options.level_mapping,
+                        ));
                     }
 
                     sarif_result
@@ -775,15 +913,71 @@ fn generate_results(
                                 .unwrap(),
                         )
                         .partial_fingerprints(partial_fingerprints);
-                    if let Some(taint_code_flow) = taint_code_flow {
-                        sarif_result.code_flows(&[taint_code_flow]);
+                    let code_flows = taint_code_flow
+                        .into_iter()
+                        .chain(rule_code_flows)
+                        .collect::<Vec<_>>();
+                    if !code_flows.is_empty() {
+                        sarif_result.code_flows(code_flows);
                     };
+                    if !related_locations.is_empty() {
+                        sarif_result.related_locations(related_locations);
+                    }
+                    let suppressions = sarif_violation.get_suppressions()?;
+                    if !suppressions.is_empty() {
+                        sarif_result.suppressions(suppressions);
+                    }
                     Ok(sarif_result.build()?)
                 })
         })
         .collect()
 }
 
+/// Builds a SARIF location with a message, pointing to a single position in `file`.
+fn generate_location(file: &str, line: u32, col: u32, message: &str) -> Result<sarif::Location> {
+    let artifact_location = ArtifactLocationBuilder::default()
+        .uri(percent_encode_path(as_slash_path(file).as_ref()))
+        .build()?;
+    Ok(LocationBuilder::default()
+        .physical_location(
+            PhysicalLocationBuilder::default()
+                .artifact_location(artifact_location)
+                .region(
+                    RegionBuilder::default()
+                        .start_line(line)
+                        .start_column(col)
+                        .build()?,
+                )
+                .build()?,
+        )
+        .message(MessageBuilder::default().text(message).build()?)
+        .build()?)
+}
+
+/// Converts a code flow reported by a rule into a SARIF `codeFlow` with a single thread flow.
+fn generate_code_flow(code_flow: &CodeFlow) -> Result<sarif::CodeFlow> {
+    let tf_locations = code_flow
+        .iter()
+        .map(|cf_location| {
+            let location = generate_location(
+                &cf_location.file,
+                cf_location.line,
+                cf_location.col,
+                &cf_location.message,
+            )?;
+            Ok(sarif::ThreadFlowLocationBuilder::default()
+                .location(location)
+                .build()?)
+        })
+        .collect::<Result<Vec<_>>>()?;
+    let thread_flow = sarif::ThreadFlowBuilder::default()
+        .locations(tf_locations)
+        .build()?;
+    Ok(sarif::CodeFlowBuilder::default()
+        .thread_flows(&[thread_flow])
+        .build()?)
+}
+
 /// A property tag to indicate an [`ArtifactClassification`] where `is_test_file` is true.
 const CLASSIFICATION_TEST_FILE: &str = "DATADOG_ARTIFACT_IS_TEST_FILE";
 
@@ -867,6 +1061,8 @@ pub fn generate_sarif_report(
         diff_aware_parameters: tool_information.diff_aware_parameters.clone(),
         repository_directory: directory.clone(),
         execution_time_secs: tool_information.execution_time_secs,
+        level_mapping: tool_information.level_mapping,
+        findings_truncated: tool_information.findings_truncated,
     };
 
     let artifacts_kv = extract_artifacts(
@@ -933,6 +1129,26 @@ pub fn generate_sarif_file(
     }
 }
 
+/// Generates the SARIF report (see [`generate_sarif_file`]) and writes it to `sink`.
+pub fn write_sarif_file(
+    sink: &dyn OutputSink,
+    configuration: &CliConfiguration,
+    static_analysis_rule_results: Vec<RuleResult>,
+    secrets_rule_results: Vec<SecretResult>,
+    sarif_report_metadata: SarifReportMetadata,
+    path_metadata: &HashMap<String, ArtifactClassification>,
+) -> Result<()> {
+    let sarif = generate_sarif_file(
+        configuration,
+        static_analysis_rule_results,
+        secrets_rule_results,
+        sarif_report_metadata,
+        path_metadata,
+    )?;
+    sink.write(sarif.as_bytes())
+        .context("error when writing the SARIF report")
+}
+
 /// Returns the file path for this result as a slash path, a path whose components are.
 /// always separated by `/` and never `\`. Any non-Unicode sequences are replaced with `U+FFFD`.
 fn as_slash_path(path_str: &str) -> std::borrow::Cow<'_, str> {
@@ -944,7 +1160,9 @@ mod tests {
     use super::*;
     use assert_json_diff::{assert_json_eq, assert_json_include};
     use common::model::position::{Position, PositionBuilder, Region};
-    use kernel::model::violation::{Fix, Violation};
+    use kernel::model::violation::{
+        CodeFlowLocation, Fix, RelatedLocation, SuppressedViolation, Violation,
+    };
     use kernel::model::{
         common::Language,
         rule::{RuleBuilder, RuleCategory, RuleResultBuilder, RuleSeverity, RuleType},
@@ -962,6 +1180,71 @@ mod tests {
         schema.validate(v).is_valid()
     }
 
+    /// Builds a Python tree-sitter rule named `my-rule`.
+    fn test_rule(category: RuleCategory, severity: RuleSeverity) -> Rule {
+        RuleBuilder::default()
+            .name("my-rule".to_string())
+            .description_base64(Some("YXdlc29tZSBydWxl".to_string()))
+            .language(Language::Python)
+            .checksum("blabla".to_string())
+            .pattern(None)
+            .tree_sitter_query_base64(Some("ts-query".to_string()))
+            .category(category)
+            .code_base64("Zm9vYmFyYmF6".to_string())
+            .short_description_base64(Some("c2hvcnQgZGVzY3JpcHRpb24=".to_string()))
+            .entity_checked(None)
+            .rule_type(RuleType::TreeSitterQuery)
+            .severity(severity)
+            .cwe(None)
+            .arguments(vec![])
+            .tests(vec![])
+            .is_testing(false)
+            .build()
+            .unwrap()
+    }
+
+    /// Returns a builder for a result of `my-rule` with the given violations, leaving the optional
+    /// fields for the caller to set.
+    fn test_rule_result(filename: &str, violations: Vec<Violation>) -> RuleResultBuilder {
+        let mut builder = RuleResultBuilder::default();
+        builder
+            .rule_name("my-rule".to_string())
+            .filename(filename.to_string())
+            .violations(violations)
+            .output(None)
+            .errors(vec![])
+            .execution_time_ms(42)
+            .parsing_time_ms(0)
+            .query_node_time_ms(0)
+            .execution_error(None);
+        builder
+    }
+
+    /// Generates the SARIF report for a single rule and its result, returning it as JSON.
+    fn generate_test_report(
+        rule: Rule,
+        rule_result: RuleResult,
+        level_mapping: HashMap<RuleSeverity, SarifLevel>,
+    ) -> Value {
+        let sarif_report = generate_sarif_report(
+            &[rule.into()],
+            &[rule_result.try_into().unwrap()],
+            &"mydir".to_string(),
+            SarifReportMetadata {
+                add_git_info: false,
+                debug: false,
+                config_digest: "5d7273dec32b80788b4d3eac46c866f0".to_string(),
+                diff_aware_parameters: None,
+                execution_time_secs: 42,
+                level_mapping,
+                findings_truncated: false,
+            },
+            &Default::default(),
+        )
+        .expect("generate sarif report");
+        serde_json::to_value(sarif_report).unwrap()
+    }
+
     #[test]
     fn test_is_valid_violation() {
         // bad location in the violation location
@@ -973,6 +1256,8 @@ mod tests {
             category: RuleCategory::BestPractices,
             fixes: vec![],
             taint_flow: None,
+            code_flows: vec![],
+            related_locations: vec![],
         }));
 
         // good location in the violation location and no fixes
@@ -984,6 +1269,8 @@ mod tests {
             category: RuleCategory::BestPractices,
             fixes: vec![],
             taint_flow: None,
+            code_flows: vec![],
+            related_locations: vec![],
         }));
 
         // bad location in the fixes location
@@ -1003,6 +1290,8 @@ mod tests {
                 }]
             }],
             taint_flow: None,
+            code_flows: vec![],
+            related_locations: vec![],
         }));
 
         // good location everywhere
@@ -1022,6 +1311,8 @@ mod tests {
                 }]
             }],
             taint_flow: None,
+            code_flows: vec![],
+            related_locations: vec![],
         }));
     }
 
@@ -1087,6 +1378,8 @@ mod tests {
             category: RuleCategory::Security,
             fixes: vec![],
             taint_flow: Some(vec![region0, region1, region2]),
+            code_flows: vec![],
+            related_locations: vec![],
         };
 
         let rule_result_single_region = RuleResultBuilder::default()
@@ -1126,6 +1419,8 @@ mod tests {
             rule_name: "java-security/flow-rule".to_string(),
             filename: "file.java".to_string(),
             violations: vec![violation_taint_flow],
+            suppressed_violations: vec![],
+            custom_tags: vec![],
             errors: vec![],
             execution_error: None,
             output: None,
@@ -1147,6 +1442,8 @@ mod tests {
                 config_digest: "5d7273dec32b80788b4d3eac46c866f0".to_string(),
                 diff_aware_parameters: None,
                 execution_time_secs: 42,
+                level_mapping: HashMap::new(),
+                findings_truncated: false,
             },
             &Default::default(),
         )
@@ -1287,6 +1584,8 @@ mod tests {
                 config_digest: "5d7273dec32b80788b4d3eac46c866f0".to_string(),
                 diff_aware_parameters: Some(diff_aware_infos),
                 execution_time_secs: 42,
+                level_mapping: HashMap::new(),
+                findings_truncated: false,
             },
             &Default::default(),
         )
@@ -1316,6 +1615,304 @@ mod tests {
         assert!(validate_data(&sarif_json));
     }
 
+    /// A report whose violations were capped by `--max-findings` is tagged as truncated.
+    #[test]
+    fn test_generate_sarif_truncated_results() {
+        let generate = |findings_truncated: bool| {
+            let sarif_report = generate_sarif_report(
+                &[],
+                &vec![],
+                &"mydir".to_string(),
+                SarifReportMetadata {
+                    add_git_info: false,
+                    debug: false,
+                    config_digest: "5d7273dec32b80788b4d3eac46c866f0".to_string(),
+                    diff_aware_parameters: None,
+                    execution_time_secs: 42,
+                    level_mapping: HashMap::new(),
+                    findings_truncated,
+                },
+                &Default::default(),
+            )
+            .expect("generate sarif report");
+            serde_json::to_value(sarif_report).unwrap()
+        };
+        let has_truncated_tag = |sarif_json: &Value| {
+            sarif_json
+                .pointer("/runs/0/tool/driver/properties/tags")
+                .unwrap()
+                .as_array()
+                .unwrap()
+                .iter()
+                .any(|val| val.as_str() == Some("DATADOG_RESULTS_TRUNCATED:true"))
+        };
+
+        let sarif_json = generate(true);
+        assert!(has_truncated_tag(&sarif_json));
+        assert!(validate_data(&sarif_json));
+        assert!(!has_truncated_tag(&generate(false)));
+    }
+
+    /// Violations suppressed by a comment are reported with an `inSource` suppression.
+    #[test]
+    fn test_generate_suppressed_violation() {
+        let rule = test_rule(RuleCategory::BestPractices, RuleSeverity::Error);
+        let violation = |line: u32, message: &str| Violation {
+            start: Position { line, col: 1 },
+            end: Position { line, col: 5 },
+            message: message.to_string(),
+            severity: RuleSeverity::Error,
+            category: RuleCategory::BestPractices,
+            fixes: vec![],
+            taint_flow: None,
+            code_flows: vec![],
+            related_locations: vec![],
+        };
+
+        let rule_result = test_rule_result("myfile.py", vec![violation(1, "reported")])
+            .suppressed_violations(vec![SuppressedViolation {
+                violation: violation(3, "suppressed"),
+                comment_line: 2,
+            }])
+            .build()
+            .expect("building violation");
+
+        let sarif_json = generate_test_report(rule, rule_result, HashMap::new());
+        let results = sarif_json
+            .pointer("/runs/0/results")
+            .unwrap()
+            .as_array()
+            .unwrap();
+        assert_eq!(results.len(), 2);
+        assert_eq!(results[0].pointer("/message/text").unwrap(), "reported");
+        assert!(results[0].get("suppressions").is_none());
+        assert_eq!(results[1].pointer("/message/text").unwrap(), "suppressed");
+        assert_json_eq!(
+            results[1].get("suppressions").unwrap(),
+            serde_json::json!([{
+                "kind": "inSource",
+                "justification": "datadog-disable comment at line 2"
+            }])
+        );
+
+        // validate the schema
+        assert!(validate_data(&sarif_json));
+    }
+
+    /// Each violation's code flows are added to that violation's result only.
+    #[test]
+    fn test_generate_code_flows() {
+        let rule = test_rule(RuleCategory::Security, RuleSeverity::Error);
+        let cf_location = |line: u32, col: u32, message: &str| CodeFlowLocation {
+            file: "my file.py".to_string(),
+            line,
+            col,
+            message: message.to_string(),
+        };
+        let violation = |line: u32| Violation {
+            start: Position { line, col: 1 },
+            end: Position { line, col: 12 },
+            message: "user input is evaluated".to_string(),
+            severity: RuleSeverity::Error,
+            category: RuleCategory::Security,
+            fixes: vec![],
+            taint_flow: None,
+            code_flows: vec![vec![
+                cf_location(1, 9, "user input is read"),
+                cf_location(line, 1, "user input is evaluated"),
+            ]],
+            related_locations: vec![],
+        };
+
+        let rule_result = test_rule_result("my file.py", vec![violation(2), violation(3)])
+            .build()
+            .expect("building violation");
+
+        let sarif_json = generate_test_report(rule, rule_result, HashMap::new());
+        assert_json_eq!(
+            sarif_json.pointer("/runs/0/results/0/codeFlows").unwrap(),
+            serde_json::json!([{
+                "threadFlows": [{
+                    "locations": [
+                        {
+                            "location": {
+                                "message": {"text": "user input is read"},
+                                "physicalLocation": {
+                                    "artifactLocation": {"uri": "my%20file.py"},
+                                    "region": {"startLine": 1, "startColumn": 9}
+                                }
+                            }
+                        },
+                        {
+                            "location": {
+                                "message": {"text": "user input is evaluated"},
+                                "physicalLocation": {
+                                    "artifactLocation": {"uri": "my%20file.py"},
+                                    "region": {"startLine": 2, "startColumn": 1}
+                                }
+                            }
+                        }
+                    ]
+                }]
+            }])
+        );
+        let second_flow = sarif_json
+            .pointer("/runs/0/results/1/codeFlows/0/threadFlows/0/locations")
+            .unwrap()
+            .as_array()
+            .unwrap();
+        assert_eq!(second_flow.len(), 2);
+        assert_eq!(
+            second_flow[1].pointer("/location/physicalLocation/region/startLine"),
+            Some(&serde_json::json!(3))
+        );
+
+        // validate the schema
+        assert!(validate_data(&sarif_json));
+    }
+
+    /// Each violation's related locations are added to that violation's result only.
+    #[test]
+    fn test_generate_related_locations() {
+        let rule = test_rule(RuleCategory::BestPractices, RuleSeverity::Error);
+        let violation = |line: u32, related_locations: Vec<RelatedLocation>| Violation {
+            start: Position { line, col: 5 },
+            end: Position { line, col: 8 },
+            message: "function redefined, see also the first declaration".to_string(),
+            severity: RuleSeverity::Error,
+            category: RuleCategory::BestPractices,
+            fixes: vec![],
+            taint_flow: None,
+            code_flows: vec![],
+            related_locations,
+        };
+        let related_location = RelatedLocation {
+            message: "first declaration".to_string(),
+            file: "myfile.py".to_string(),
+            line: 1,
+            col: 5,
+        };
+
+        let rule_result = test_rule_result(
+            "myfile.py",
+            vec![violation(5, vec![related_location]), violation(9, vec![])],
+        )
+        .build()
+        .expect("building violation");
+
+        let sarif_json = generate_test_report(rule, rule_result, HashMap::new());
+        assert_json_eq!(
+            sarif_json
+                .pointer("/runs/0/results/0/relatedLocations")
+                .unwrap(),
+            serde_json::json!([{
+                "id": 0,
+                "message": {"text": "first declaration"},
+                "physicalLocation": {
+                    "artifactLocation": {"uri": "myfile.py"},
+                    "region": {"startLine": 1, "startColumn": 5}
+                }
+            }])
+        );
+        assert!(sarif_json
+            .pointer("/runs/0/results/1/relatedLocations")
+            .is_none());
+
+        // validate the schema
+        assert!(validate_data(&sarif_json));
+    }
+
+    /// Custom tags from the rule configuration are added to the result's tags.
+    #[test]
+    fn test_generate_custom_tags() {
+        let rule = test_rule(RuleCategory::Security, RuleSeverity::Error);
+        let violation = Violation {
+            start: Position { line: 10, col: 12 },
+            end: Position { line: 12, col: 10 },
+            message: "something bad happened".to_string(),
+            severity: RuleSeverity::Error,
+            category: RuleCategory::Security,
+            fixes: vec![],
+            taint_flow: None,
+            code_flows: vec![],
+            related_locations: vec![],
+        };
+
+        let rule_result = test_rule_result("myfile.py", vec![violation])
+            .custom_tags(vec![
+                "team:security".to_string(),
+                "jira-project:SEC".to_string(),
+            ])
+            .build()
+            .expect("building violation");
+
+        let sarif_json = generate_test_report(rule, rule_result, HashMap::new());
+        assert_json_eq!(
+            sarif_json
+                .pointer("/runs/0/results/0/properties/tags")
+                .unwrap(),
+            serde_json::json!([
+                "DATADOG_CATEGORY:SECURITY",
+                "team:security",
+                "jira-project:SEC"
+            ])
+        );
+
+        // validate the schema
+        assert!(validate_data(&sarif_json));
+    }
+
+    /// A custom level mapping overrides the default level of a rule's results.
+    #[test]
+    fn test_generate_custom_level_mapping() {
+        let rule = test_rule(RuleCategory::BestPractices, RuleSeverity::Warning);
+        let violation = Violation {
+            start: Position { line: 10, col: 12 },
+            end: Position { line: 12, col: 10 },
+            message: "something bad happened".to_string(),
+            severity: RuleSeverity::Warning,
+            category: RuleCategory::BestPractices,
+            fixes: vec![],
+            taint_flow: None,
+            code_flows: vec![],
+            related_locations: vec![],
+        };
+
+        let rule_result = test_rule_result("myfile.py", vec![violation])
+            .build()
+            .expect("building violation");
+
+        let level_mapping = parse_sarif_level_mapping(r#"{"Warning":"note","Error":"error"}"#)
+            .expect("valid mapping");
+        let sarif_json = generate_test_report(rule, rule_result, level_mapping);
+        assert_eq!(
+            sarif_json.pointer("/runs/0/results/0/level").unwrap(),
+            "note"
+        );
+
+        // validate the schema
+        assert!(validate_data(&sarif_json));
+    }
+
+    /// Level mappings are parsed case-insensitively, and unknown values are rejected.
+    #[test]
+    fn test_parse_sarif_level_mapping() {
+        let mapping = parse_sarif_level_mapping(r#"{"Warning":"note","ERROR":"Warning"}"#).unwrap();
+        assert_eq!(
+            mapping,
+            HashMap::from([
+                (RuleSeverity::Warning, SarifLevel::Note),
+                (RuleSeverity::Error, SarifLevel::Warning),
+            ])
+        );
+
+        let err = parse_sarif_level_mapping(r#"{"Critical":"error"}"#).unwrap_err();
+        assert!(err.to_string().contains("unknown severity `Critical`"));
+        let err = parse_sarif_level_mapping(r#"{"Error":"fatal"}"#).unwrap_err();
+        assert!(err.to_string().contains("unknown SARIF level `fatal`"));
+        assert!(parse_sarif_level_mapping(r#"["Error"]"#).is_err());
+    }
+
     /// Tests that artifact URIs are percent-encoded.
     #[test]
     fn test_generate_with_escape_characters() {
@@ -1383,6 +1980,8 @@ mod tests {
                 config_digest: "5d7273dec32b80788b4d3eac46c866f0".to_string(),
                 diff_aware_parameters: None,
                 execution_time_secs: 42,
+                level_mapping: HashMap::new(),
+                findings_truncated: false,
             },
             &Default::default(),
         )
@@ -1481,6 +2080,8 @@ mod tests {
                     config_digest: "5d7273dec32b80788b4d3eac46c866f0".to_string(),
                     diff_aware_parameters: None,
                     execution_time_secs: 42,
+                    level_mapping: HashMap::new(),
+                    findings_truncated: false,
                 },
                 &Default::default(),
             )
@@ -1628,6 +2229,8 @@ mod tests {
                 config_digest: "5d7273dec32b80788b4d3eac46c866f0".to_string(),
                 diff_aware_parameters: None,
                 execution_time_secs: 42,
+                level_mapping: HashMap::new(),
+                findings_truncated: false,
             },
             &Default::default(),
         )
@@ -1664,11 +2267,15 @@ mod tests {
                     category: RuleCategory::BestPractices,
                     fixes: vec![],
                     taint_flow: None,
+                    code_flows: vec![],
+                    related_locations: vec![],
                 };
                 let rr = RuleResult {
                     rule_name: format!("rule-{idx}"),
                     filename: file_path.to_string(),
                     violations: vec![violation],
+                    suppressed_violations: vec![],
+                    custom_tags: vec![],
                     errors: vec![],
                     execution_error: None,
                     output: None,
@@ -1693,6 +2300,8 @@ mod tests {
                 config_digest: "5d7273dec32b80788b4d3eac46c866f0".to_string(),
                 diff_aware_parameters: None,
                 execution_time_secs: 42,
+                level_mapping: HashMap::new(),
+                findings_truncated: false,
             },
             &Default::default(),
         )
@@ -1756,6 +2365,8 @@ mod tests {
             category: RuleCategory::BestPractices,
             fixes: vec![],
             taint_flow: None,
+            code_flows: vec![],
+            related_locations: vec![],
         };
         let rule_results = [TEST_FILE_PATH, NON_TEST_FILE_PATH]
             .into_iter()
@@ -1764,6 +2375,8 @@ mod tests {
                     rule_name: "ruleset/rule-name".to_string(),
                     filename: path.to_string(),
                     violations: vec![violation.clone()],
+                    suppressed_violations: vec![],
+                    custom_tags: vec![],
                     errors: vec![],
                     execution_error: None,
                     output: None,
@@ -1790,6 +2403,8 @@ mod tests {
                 config_digest: "5d7273dec32b80788b4d3eac46c866f0".to_string(),
                 diff_aware_parameters: None,
                 execution_time_secs: 42,
+                level_mapping: HashMap::new(),
+                findings_truncated: false,
             },
             &path_metadata,
         )
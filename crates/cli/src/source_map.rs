@@ -0,0 +1,354 @@
+use common::model::position::Position;
+use kernel::model::rule::RuleResult;
+use sourcemap::SourceMap;
+use std::collections::HashMap;
+use std::fs::File;
+use std::path::{Component, Path};
+
+/// Translates the positions of the rule results back to the original (pre-transpiled) files.
+///
+/// The source map of a file is read from `<source_map_directory>/<filename>.map`. A translated
+/// result uses the source file that its positions map to: the source is resolved against the
+/// source map's `sourceRoot` and the directory of the transpiled file, like a browser would.
+/// Results for a file without a (valid) source map are returned unchanged, and so are results with
+/// a position (including the line of a suppression comment) that isn't mapped to that source file.
+///
+/// Fixes are removed from translated violations, because their edits apply to the transpiled file.
+pub fn translate_rule_results(
+    rule_results: Vec<RuleResult>,
+    source_map_directory: &Path,
+) -> Vec<RuleResult> {
+    let mut source_maps: HashMap<String, Option<SourceMap>> = HashMap::new();
+    rule_results
+        .into_iter()
+        .map(|rule_result| {
+            let source_map = source_maps
+                .entry(rule_result.filename.clone())
+                .or_insert_with(|| read_source_map(source_map_directory, &rule_result.filename));
+            match source_map {
+                Some(source_map) => {
+                    translate_rule_result(&rule_result, source_map).unwrap_or(rule_result)
+                }
+                None => rule_result,
+            }
+        })
+        .collect()
+}
+
+/// Reads the source map for `filename`, if it exists.
+fn read_source_map(source_map_directory: &Path, filename: &str) -> Option<SourceMap> {
+    let path = source_map_directory.join(format!("{filename}.map"));
+    let file = File::open(path).ok()?;
+    SourceMap::from_reader(file).ok()
+}
+
+/// Resolves a `source` of the source map of the transpiled file `filename` to a path relative to
+/// the repository. Relative sources are relative to the directory of the transpiled file, while
+/// URLs and absolute paths are returned as-is.
+fn resolve_source(filename: &str, source: &str) -> String {
+    if source.contains("://") || Path::new(source).is_absolute() {
+        return source.to_string();
+    }
+    let directory = Path::new(filename).parent().unwrap_or(Path::new(""));
+    let mut components = Vec::<&str>::new();
+    for component in directory.join(source).components() {
+        match component {
+            Component::Normal(name) => components.push(name.to_str().unwrap_or_default()),
+            Component::ParentDir => {
+                components.pop();
+            }
+            _ => {}
+        }
+    }
+    components.join("/")
+}
+
+/// Returns the source file and 1-based position in that file that `position` (in the transpiled
+/// file) maps to.
+fn lookup<'a>(source_map: &'a SourceMap, position: Position) -> Option<(&'a str, Position)> {
+    let line = position.line.checked_sub(1)?;
+    let col = position.col.checked_sub(1)?;
+    let token = source_map.lookup_token(line, col)?;
+    let source = token.get_source()?;
+    // A position within the token is offset from the token's start by the same amount.
+    let offset = if token.get_dst_line() == line {
+        col - token.get_dst_col()
+    } else {
+        0
+    };
+    let original = Position::new(token.get_src_line() + 1, token.get_src_col() + offset + 1);
+    Some((source, original))
+}
+
+/// Returns the source file and 1-based line in that file that `line` (in the transpiled file)
+/// maps to, based on the tokens of that line.
+fn lookup_line(source_map: &SourceMap, line: u32) -> Option<(&str, u32)> {
+    let dst_line = line.checked_sub(1)?;
+    let token = source_map
+        .lookup_token(dst_line, u32::MAX)
+        .filter(|token| token.get_dst_line() == dst_line)?;
+    Some((token.get_source()?, token.get_src_line() + 1))
+}
+
+/// Translates all the positions of `rule_result`, returning `None` if any of them isn't mapped
+/// to the same source file.
+fn translate_rule_result(rule_result: &RuleResult, source_map: &SourceMap) -> Option<RuleResult> {
+    // The source file is the one of the first violation, or the only source of the source map
+    // for a result without violations.
+    let first_violation = rule_result
+        .violations
+        .iter()
+        .chain(
+            rule_result
+                .suppressed_violations
+                .iter()
+                .map(|s| &s.violation),
+        )
+        .next();
+    let source = match first_violation {
+        Some(violation) => lookup(source_map, violation.start)?.0,
+        None if source_map.get_source_count() == 1 => source_map.get_source(0)?,
+        None => return None,
+    };
+    // Translates a position, as long as it maps to the same source file.
+    let translate = |position: Position| match lookup(source_map, position) {
+        Some((src, original)) if src == source => Some(original),
+        _ => None,
+    };
+    let original_filename = resolve_source(&rule_result.filename, source);
+
+    let mut rule_result = rule_result.clone();
+    let violations = rule_result.violations.iter_mut().chain(
+        rule_result
+            .suppressed_violations
+            .iter_mut()
+            .map(|s| &mut s.violation),
+    );
+    for violation in violations {
+        violation.start = translate(violation.start)?;
+        violation.end = translate(violation.end)?;
+        violation.fixes.clear();
+        for region in violation.taint_flow.iter_mut().flatten() {
+            region.start = translate(region.start)?;
+            region.end = translate(region.end)?;
+        }
+        for location in violation.code_flows.iter_mut().flatten() {
+            if location.file == rule_result.filename {
+                let original = translate(Position::new(location.line, location.col))?;
+                location.file.clone_from(&original_filename);
+                location.line = original.line;
+                location.col = original.col;
+            }
+        }
+        for location in &mut violation.related_locations {
+            if location.file == rule_result.filename {
+                let original = translate(Position::new(location.line, location.col))?;
+                location.file.clone_from(&original_filename);
+                location.line = original.line;
+                location.col = original.col;
+            }
+        }
+    }
+    for suppressed in &mut rule_result.suppressed_violations {
+        suppressed.comment_line = match lookup_line(source_map, suppressed.comment_line) {
+            Some((src, line)) if src == source => line,
+            _ => return None,
+        };
+    }
+    rule_result.filename = original_filename;
+    Some(rule_result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use kernel::model::rule::{RuleCategory, RuleSeverity};
+    use kernel::model::violation::{SuppressedViolation, Violation};
+    use sourcemap::SourceMapBuilder;
+
+    fn rule_result(filename: &str, start: Position, end: Position) -> RuleResult {
+        RuleResult {
+            rule_name: "ts-rules/rule".to_string(),
+            filename: filename.to_string(),
+            violations: vec![Violation {
+                start,
+                end,
+                message: "message".to_string(),
+                severity: RuleSeverity::Warning,
+                category: RuleCategory::BestPractices,
+                fixes: vec![],
+                taint_flow: None,
+                code_flows: vec![],
+                related_locations: vec![],
+            }],
+            suppressed_violations: vec![],
+            custom_tags: vec![],
+            errors: vec![],
+            execution_error: None,
+            output: None,
+            execution_time_ms: 0,
+            parsing_time_ms: 0,
+            query_node_time_ms: 0,
+        }
+    }
+
+    /// Writes the source map of the transpiled `filename`, built from `tokens` of
+    /// `(dst_line, dst_col, src_line, src_col, source)` (with 0-based lines and columns).
+    fn write_source_map_with(
+        directory: &Path,
+        filename: &str,
+        source_root: Option<&str>,
+        tokens: &[(u32, u32, u32, u32, &str)],
+    ) {
+        let mut builder = SourceMapBuilder::new(Some(filename));
+        builder.set_source_root(source_root);
+        for &(dst_line, dst_col, src_line, src_col, source) in tokens {
+            builder.add(
+                dst_line,
+                dst_col,
+                src_line,
+                src_col,
+                Some(source),
+                None,
+                false,
+            );
+        }
+        let source_map = builder.into_sourcemap();
+        let path = directory.join(format!("{filename}.map"));
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        let file = File::create(path).unwrap();
+        source_map.to_writer(file).unwrap();
+    }
+
+    // The transpiled `dist/app.js` has its line 1 generated from line 3 of `src/app.ts`,
+    // with the code shifted 4 columns to the left.
+    fn write_source_map(directory: &Path) {
+        write_source_map_with(
+            directory,
+            "dist/app.js",
+            None,
+            &[(0, 0, 2, 4, "../src/app.ts")],
+        );
+    }
+
+    #[test]
+    fn test_translate_rule_results() {
+        let tempdir = tempfile::tempdir().unwrap();
+        write_source_map(tempdir.path());
+
+        let results = vec![
+            rule_result("dist/app.js", Position::new(1, 7), Position::new(1, 12)),
+            rule_result("dist/other.js", Position::new(1, 7), Position::new(1, 12)),
+        ];
+        let results = translate_rule_results(results, tempdir.path());
+
+        assert_eq!(results[0].filename, "src/app.ts");
+        assert_eq!(results[0].violations[0].start, Position::new(3, 11));
+        assert_eq!(results[0].violations[0].end, Position::new(3, 16));
+        // Files without a source map are left untouched.
+        assert_eq!(results[1].filename, "dist/other.js");
+        assert_eq!(results[1].violations[0].start, Position::new(1, 7));
+    }
+
+    /// Sources are resolved against the source map's `sourceRoot` and the transpiled file's directory.
+    #[test]
+    fn test_translate_rule_results_source_root() {
+        let tempdir = tempfile::tempdir().unwrap();
+        write_source_map_with(
+            tempdir.path(),
+            "dist/js/app.js",
+            Some("../../src"),
+            &[(0, 0, 2, 4, "./app.ts")],
+        );
+        write_source_map_with(
+            tempdir.path(),
+            "dist/lib.js",
+            Some("webpack://"),
+            &[(0, 0, 2, 4, "lib.ts")],
+        );
+
+        let results = vec![
+            rule_result("dist/js/app.js", Position::new(1, 7), Position::new(1, 12)),
+            rule_result("dist/lib.js", Position::new(1, 7), Position::new(1, 12)),
+        ];
+        let results = translate_rule_results(results, tempdir.path());
+
+        assert_eq!(results[0].filename, "src/app.ts");
+        assert_eq!(results[1].filename, "webpack://lib.ts");
+    }
+
+    /// A result with a position that isn't mapped to the same source file is left untouched.
+    #[test]
+    fn test_translate_rule_results_unmapped_position() {
+        let tempdir = tempfile::tempdir().unwrap();
+        write_source_map_with(
+            tempdir.path(),
+            "dist/app.js",
+            None,
+            &[
+                (0, 0, 2, 4, "../src/app.ts"),
+                (1, 0, 0, 0, "../src/other.ts"),
+            ],
+        );
+
+        let results = vec![rule_result(
+            "dist/app.js",
+            Position::new(1, 7),
+            Position::new(2, 3),
+        )];
+        let results = translate_rule_results(results, tempdir.path());
+
+        assert_eq!(results[0].filename, "dist/app.js");
+        assert_eq!(results[0].violations[0].start, Position::new(1, 7));
+        assert_eq!(results[0].violations[0].end, Position::new(2, 3));
+    }
+
+    /// Suppressed violations are translated, including the line of their suppression comment.
+    #[test]
+    fn test_translate_rule_results_suppressed_violations() {
+        let tempdir = tempfile::tempdir().unwrap();
+        write_source_map_with(
+            tempdir.path(),
+            "dist/app.js",
+            None,
+            &[(0, 0, 4, 0, "../src/app.ts"), (1, 0, 5, 4, "../src/app.ts")],
+        );
+
+        let mut result = rule_result("dist/app.js", Position::new(2, 7), Position::new(2, 12));
+        result.suppressed_violations = vec![SuppressedViolation {
+            violation: result.violations.remove(0),
+            comment_line: 1,
+        }];
+        let results = translate_rule_results(vec![result], tempdir.path());
+
+        assert_eq!(results[0].filename, "src/app.ts");
+        assert!(results[0].violations.is_empty());
+        let suppressed = &results[0].suppressed_violations[0];
+        assert_eq!(suppressed.violation.start, Position::new(6, 11));
+        assert_eq!(suppressed.violation.end, Position::new(6, 16));
+        assert_eq!(suppressed.comment_line, 5);
+    }
+
+    /// A result without violations uses the only source of the source map, if there is one.
+    #[test]
+    fn test_translate_rule_results_no_violations() {
+        let tempdir = tempfile::tempdir().unwrap();
+        write_source_map(tempdir.path());
+        write_source_map_with(
+            tempdir.path(),
+            "dist/bundle.js",
+            None,
+            &[(0, 0, 0, 0, "../src/a.ts"), (1, 0, 0, 0, "../src/b.ts")],
+        );
+
+        let mut results = vec![
+            rule_result("dist/app.js", Position::new(1, 7), Position::new(1, 12)),
+            rule_result("dist/bundle.js", Position::new(1, 7), Position::new(1, 12)),
+        ];
+        results.iter_mut().for_each(|r| r.violations.clear());
+        let results = translate_rule_results(results, tempdir.path());
+
+        assert_eq!(results[0].filename, "src/app.ts");
+        assert_eq!(results[1].filename, "dist/bundle.js");
+    }
+}
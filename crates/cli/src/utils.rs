@@ -4,6 +4,7 @@ use crate::rule_utils::get_languages_for_rules;
 use kernel::constants::{CARGO_VERSION, VERSION};
 use kernel::model::common::OutputFormat;
 use kernel::model::config_file::ConfigMethod;
+use std::io::{self, Write};
 
 /// Returns the user's requested core count, clamped to the number of logical cores on the system.
 /// If unspecified, up to [DEFAULT_MAX_CPUS] CPUs will be used.
@@ -26,7 +27,11 @@ pub fn get_num_threads_to_use(configuration: &CliConfiguration) -> usize {
     }
 }
 
-pub fn print_configuration(configuration: &CliConfiguration) {
+/// Writes a summary of the configuration to `out`.
+pub fn print_configuration(
+    out: &mut dyn Write,
+    configuration: &CliConfiguration,
+) -> io::Result<()> {
     let configuration_method = match configuration.configuration_method {
         None => "none (no local file and no remote configuration)",
         Some(ConfigMethod::RemoteConfiguration) => "remote configuration",
@@ -52,56 +57,87 @@ pub fn print_configuration(configuration: &CliConfiguration) {
         None => "all paths".to_string(),
     };
 
-    println!("Configuration");
-    println!("=============");
-    println!("version                 : {}", CARGO_VERSION);
-    println!("revision                : {}", VERSION);
-    println!("config method           : {}", configuration_method);
-    println!("cores available         : {}", num_cpus::get());
-    println!("cores used              : {}", configuration.num_cpus);
-    println!("#static analysis rules  : {}", configuration.rules.len());
+    writeln!(out, "Configuration")?;
+    writeln!(out, "=============")?;
+    writeln!(out, "version                 : {}", CARGO_VERSION)?;
+    writeln!(out, "revision                : {}", VERSION)?;
+    writeln!(out, "config method           : {}", configuration_method)?;
+    writeln!(out, "cores available         : {}", num_cpus::get())?;
+    writeln!(out, "cores used              : {}", configuration.num_cpus)?;
+    writeln!(
+        out,
+        "#static analysis rules  : {}",
+        configuration.rules.len()
+    )?;
 
     if configuration.secrets_enabled {
-        println!(
+        writeln!(
+            out,
             "#secrets rules loaded   : {}",
             configuration.secrets_rules.len()
-        );
+        )?;
     }
 
-    println!(
+    writeln!(
+        out,
         "source directory        : {}",
         configuration.source_directory
-    );
-    println!(
+    )?;
+    writeln!(
+        out,
         "subdirectories          : {}",
         configuration.source_subdirectories.clone().join(",")
-    );
+    )?;
 
-    println!("output file             : {}", configuration.output_file);
-    println!(
+    writeln!(
+        out,
+        "output file             : {}",
+        configuration
+            .output_file
+            .as_deref()
+            .unwrap_or("standard output")
+    )?;
+    writeln!(
+        out,
         "static analysis enabled:  {}",
         configuration.static_analysis_enabled
-    );
-    println!(
+    )?;
+    writeln!(
+        out,
         "secrets enabled         : {}",
         configuration.secrets_enabled
-    );
-    println!("output format           : {}", output_format_str);
-    println!("ignore paths            : {}", ignore_paths_str);
-    println!("only paths              : {}", only_paths_str);
-    println!(
+    )?;
+    writeln!(out, "output format           : {}", output_format_str)?;
+    writeln!(out, "ignore paths            : {}", ignore_paths_str)?;
+    writeln!(out, "only paths              : {}", only_paths_str)?;
+    writeln!(
+        out,
         "ignore gitignore        : {}",
         configuration.ignore_gitignore
-    );
-    println!("use debug               : {}", configuration.use_debug);
-    println!("use staging             : {}", configuration.use_staging);
-    println!(
+    )?;
+    writeln!(out, "use debug               : {}", configuration.use_debug)?;
+    writeln!(
+        out,
+        "use staging             : {}",
+        configuration.use_staging
+    )?;
+    writeln!(
+        out,
         "ignore gen files        : {}",
         configuration.ignore_generated_files
-    );
-    println!("rules languages         : {}", languages_string.join(","));
-    println!(
+    )?;
+    writeln!(
+        out,
+        "rules languages         : {}",
+        languages_string.join(",")
+    )?;
+    writeln!(
+        out,
         "max file size           : {} kb",
         configuration.max_file_size_kb
-    );
+    )?;
+    if let Some(max_findings) = configuration.max_findings {
+        writeln!(out, "max findings            : {}", max_findings)?;
+    }
+    Ok(())
 }
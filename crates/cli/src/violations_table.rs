@@ -1,7 +1,9 @@
 use kernel::model::rule::RuleResult;
 use prettytable::{format, row, Table};
+use std::io::{self, Write};
 
-pub fn print_violations_table(rule_results: &[RuleResult]) {
+/// Writes a table with all the violations of `rule_results` to `out`.
+pub fn print_violations_table(out: &mut dyn Write, rule_results: &[RuleResult]) -> io::Result<()> {
     let mut table = Table::new();
     let format = format::FormatBuilder::new()
         .separator(
@@ -35,5 +37,6 @@ pub fn print_violations_table(rule_results: &[RuleResult]) {
             }
         }
     }
-    table.printstd();
+    table.print(out)?;
+    Ok(())
 }
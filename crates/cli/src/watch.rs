@@ -0,0 +1,162 @@
+use std::collections::HashMap;
+use std::fs;
+use std::path::PathBuf;
+use std::time::{Duration, Instant, SystemTime};
+
+use anyhow::Result;
+use kernel::model::config_file::PathConfig;
+
+use crate::file_utils::get_files;
+
+/// Detects the files of a directory that changed, by polling their modification time.
+///
+/// A change is only reported once the file has not been modified again for the `debounce` delay,
+/// so that a burst of saves leads to a single analysis.
+pub struct FileWatcher {
+    directory: String,
+    subdirectories: Vec<String>,
+    path_config: PathConfig,
+    debounce: Duration,
+    /// The last modification time of each file.
+    modified: HashMap<PathBuf, SystemTime>,
+    /// The files that changed and have not been reported yet, with the time of their last change.
+    pending: HashMap<PathBuf, Instant>,
+}
+
+impl FileWatcher {
+    /// Creates a watcher for the files returned by [`get_files`]: the files that exist when the
+    /// watcher is created are not reported until they change.
+    pub fn new(
+        directory: &str,
+        subdirectories: Vec<String>,
+        path_config: PathConfig,
+        debounce: Duration,
+    ) -> Result<FileWatcher> {
+        let mut watcher = FileWatcher {
+            directory: directory.to_string(),
+            subdirectories,
+            path_config,
+            debounce,
+            modified: HashMap::new(),
+            pending: HashMap::new(),
+        };
+        watcher.modified = watcher.modification_times()?;
+        Ok(watcher)
+    }
+
+    fn modification_times(&self) -> Result<HashMap<PathBuf, SystemTime>> {
+        let files = get_files(
+            &self.directory,
+            self.subdirectories.clone(),
+            &self.path_config,
+        )?;
+        Ok(files
+            .into_iter()
+            .filter_map(|path| {
+                let modified = fs::metadata(&path).and_then(|m| m.modified()).ok()?;
+                Some((path, modified))
+            })
+            .collect())
+    }
+
+    /// Returns the files that were created or modified since the previous poll, and that have not
+    /// been modified for the debounce delay since. The files are sorted by path.
+    pub fn poll(&mut self) -> Result<Vec<PathBuf>> {
+        let now = Instant::now();
+        let modified = self.modification_times()?;
+        for (path, time) in &modified {
+            if self.modified.get(path) != Some(time) {
+                self.pending.insert(path.clone(), now);
+            }
+        }
+        // Deleted files are not reported.
+        self.pending.retain(|path, _| modified.contains_key(path));
+        self.modified = modified;
+
+        let mut changed = self
+            .pending
+            .iter()
+            .filter(|(_, &time)| now.duration_since(time) >= self.debounce)
+            .map(|(path, _)| path.clone())
+            .collect::<Vec<_>>();
+        for path in &changed {
+            self.pending.remove(path);
+        }
+        changed.sort();
+        Ok(changed)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs::File;
+    use std::path::Path;
+
+    /// Sets the modification time of `path` to `seconds` after the epoch.
+    fn set_modified(path: &Path, seconds: u64) {
+        let file = File::options().write(true).open(path).unwrap();
+        file.set_modified(SystemTime::UNIX_EPOCH + Duration::from_secs(seconds))
+            .unwrap();
+    }
+
+    #[test]
+    fn test_poll_reports_created_and_modified_files() {
+        let tempdir = tempfile::tempdir().unwrap();
+        let existing = tempdir.path().join("existing.py");
+        fs::write(&existing, "a = 1").unwrap();
+        set_modified(&existing, 1);
+        let directory = tempdir.path().to_str().unwrap();
+        let mut watcher =
+            FileWatcher::new(directory, vec![], PathConfig::default(), Duration::ZERO).unwrap();
+        assert!(watcher.poll().unwrap().is_empty());
+
+        let created = tempdir.path().join("created.py");
+        fs::write(&created, "b = 2").unwrap();
+        set_modified(&existing, 2);
+        assert_eq!(watcher.poll().unwrap(), vec![created, existing]);
+        assert!(watcher.poll().unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_poll_respects_path_config() {
+        let tempdir = tempfile::tempdir().unwrap();
+        let directory = tempdir.path().to_str().unwrap();
+        let path_config = PathConfig {
+            only: None,
+            ignore: vec!["ignored.py".to_string().into()],
+        };
+        let mut watcher = FileWatcher::new(directory, vec![], path_config, Duration::ZERO).unwrap();
+
+        fs::write(tempdir.path().join("ignored.py"), "a = 1").unwrap();
+        let analyzed = tempdir.path().join("analyzed.py");
+        fs::write(&analyzed, "b = 2").unwrap();
+        assert_eq!(watcher.poll().unwrap(), vec![analyzed]);
+    }
+
+    #[test]
+    fn test_poll_debounces_changes() {
+        let tempdir = tempfile::tempdir().unwrap();
+        let directory = tempdir.path().to_str().unwrap();
+        let debounce = Duration::from_millis(200);
+        let mut watcher =
+            FileWatcher::new(directory, vec![], PathConfig::default(), debounce).unwrap();
+
+        let file = tempdir.path().join("main.py");
+        fs::write(&file, "a = 1").unwrap();
+        assert!(watcher.poll().unwrap().is_empty());
+        std::thread::sleep(debounce);
+        assert_eq!(watcher.poll().unwrap(), vec![file.clone()]);
+
+        // A file that keeps changing is not reported.
+        set_modified(&file, 1);
+        assert!(watcher.poll().unwrap().is_empty());
+        set_modified(&file, 2);
+        assert!(watcher.poll().unwrap().is_empty());
+
+        // A deleted file is not reported.
+        fs::remove_file(&file).unwrap();
+        std::thread::sleep(debounce);
+        assert!(watcher.poll().unwrap().is_empty());
+    }
+}
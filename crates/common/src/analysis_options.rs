@@ -1,7 +1,34 @@
+use std::path::PathBuf;
 use std::time::Duration;
 
 use serde::{Deserialize, Serialize};
 
+/// What the analysis does when a rule exceeds its execution timeout.
+#[derive(Copy, Clone, Deserialize, Debug, Default, Serialize, PartialEq, Eq)]
+pub enum RuleTimeoutAction {
+    /// Report a `rule-timeout` error in the rule's result.
+    #[default]
+    EmitError,
+    /// Don't report any result for the rule.
+    Skip,
+    /// Report a `rule-timeout` error and stop the analysis: the remaining rules aren't run on the
+    /// file, and callers analyzing several files don't analyze the remaining files.
+    Abort,
+}
+
+impl TryFrom<&str> for RuleTimeoutAction {
+    type Error = &'static str;
+
+    fn try_from(s: &str) -> Result<Self, &'static str> {
+        match s.to_lowercase().as_str() {
+            "error" => Ok(RuleTimeoutAction::EmitError),
+            "skip" => Ok(RuleTimeoutAction::Skip),
+            "abort" => Ok(RuleTimeoutAction::Abort),
+            _ => Err("unknown rule timeout action"),
+        }
+    }
+}
+
 // Used internally to pass options to the analysis
 #[derive(Clone, Deserialize, Debug, Serialize)]
 pub struct AnalysisOptions {
@@ -9,6 +36,10 @@ pub struct AnalysisOptions {
     pub use_debug: bool,
     pub ignore_generated_files: bool,
     pub timeout: Option<Duration>,
+    pub rule_timeout_action: RuleTimeoutAction,
+    /// A directory containing the source maps (`<file>.map`) of transpiled files. When set, findings
+    /// are reported against the original files.
+    pub source_map_directory: Option<PathBuf>,
 }
 
 impl Default for AnalysisOptions {
@@ -18,6 +49,30 @@ impl Default for AnalysisOptions {
             use_debug: false,
             ignore_generated_files: true,
             timeout: None,
+            rule_timeout_action: RuleTimeoutAction::default(),
+            source_map_directory: None,
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rule_timeout_action_try_from() {
+        assert_eq!(
+            RuleTimeoutAction::try_from("error"),
+            Ok(RuleTimeoutAction::EmitError)
+        );
+        assert_eq!(
+            RuleTimeoutAction::try_from("Skip"),
+            Ok(RuleTimeoutAction::Skip)
+        );
+        assert_eq!(
+            RuleTimeoutAction::try_from("abort"),
+            Ok(RuleTimeoutAction::Abort)
+        );
+        assert!(RuleTimeoutAction::try_from("ignore").is_err());
+    }
+}
@@ -2,10 +2,17 @@
 // This product includes software developed at Datadog (https://www.datadoghq.com/).
 // Copyright 2024 Datadog, Inc.
 
+use bstr::ByteSlice;
 use derive_builder::Builder;
 use serde::{Deserialize, Serialize};
 use std::fmt;
 
+/// A 1-based position within a file.
+///
+/// The unit of `col` depends on how the position was produced: positions of tree-sitter nodes
+/// count UTF-8 bytes, whereas [`Position::from_byte_offset`] (and therefore
+/// [`get_position_in_string`](crate::utils::position_utils::get_position_in_string)) count
+/// extended grapheme clusters, which is what an editor displays as a single column.
 #[derive(Deserialize, Debug, Serialize, Clone, Copy, Builder, PartialEq, Eq, Hash)]
 pub struct Position {
     pub line: u32,
@@ -16,6 +23,48 @@ impl Position {
     pub fn new(line: u32, col: u32) -> Self {
         Self { line, col }
     }
+
+    /// Returns the 1-based position of the byte `offset` within `content`, where the column is
+    /// counted in extended grapheme clusters. An `offset` within a grapheme cluster resolves to
+    /// the column after that cluster.
+    ///
+    /// This is `O(offset)`: callers converting many offsets in the same content should instead
+    /// precompute an index of the line breaks.
+    ///
+    /// # Panics
+    /// Panics if `offset` is greater than the length of `content`.
+    pub fn from_byte_offset(offset: usize, content: &str) -> Self {
+        let prefix = &content.as_bytes()[..offset];
+        let line_start = prefix.rfind_byte(b'\n').map_or(0, |idx| idx + 1);
+        let line = prefix.find_iter("\n").count() + 1;
+        let col = prefix[line_start..].graphemes().count() + 1;
+        Self {
+            line: u32::try_from(line).expect("line should fit in a u32"),
+            col: u32::try_from(col).expect("col should fit in a u32"),
+        }
+    }
+
+    /// Returns the byte offset of this position within `content`, or `None` if the position
+    /// is not within `content`. This is the inverse of [`Position::from_byte_offset`].
+    ///
+    /// A column one past the last grapheme cluster of a line is valid, and points to the line's
+    /// terminator (or to the end of `content`).
+    pub fn to_byte_offset(&self, content: &str) -> Option<usize> {
+        let line_idx = (self.line as usize).checked_sub(1)?;
+        let col_idx = (self.col as usize).checked_sub(1)?;
+        let line_start = match line_idx {
+            0 => 0,
+            n => content.match_indices('\n').nth(n - 1)?.0 + 1,
+        };
+        let line = &content[line_start..];
+        let line = &line[..line.find('\n').unwrap_or(line.len())];
+        line.as_bytes()
+            .grapheme_indices()
+            .map(|(idx, _, _)| idx)
+            .chain(std::iter::once(line.len()))
+            .nth(col_idx)
+            .map(|idx| line_start + idx)
+    }
 }
 
 impl fmt::Display for Position {
@@ -36,3 +85,61 @@ pub struct Region {
     /// * `col`: A positive integer whose value is one greater than column number of the last character in this region.
     pub end: Position,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::Position;
+
+    #[test]
+    fn from_byte_offset_multiline_unicode() {
+        let text = "The quick\n🦊 jumps\nthe lazy 🐕";
+        // Start of the content
+        assert_eq!(Position::from_byte_offset(0, text), Position::new(1, 1));
+        // Middle of a line, after a multi-byte char
+        let jumps = text.find("jumps").unwrap();
+        assert_eq!(Position::from_byte_offset(jumps, text), Position::new(2, 3));
+        // The line terminator belongs to the line it ends
+        assert_eq!(Position::from_byte_offset(9, text), Position::new(1, 10));
+        // Start of a line
+        assert_eq!(Position::from_byte_offset(10, text), Position::new(2, 1));
+        // End of the content
+        assert_eq!(
+            Position::from_byte_offset(text.len(), text),
+            Position::new(3, 11)
+        );
+    }
+
+    #[test]
+    fn from_byte_offset_grapheme_clusters() {
+        // "é" is written as an "e" followed by a combining acute accent (2 chars, 1 grapheme cluster).
+        let text = "cafe\u{301} ok";
+        let ok = text.find("ok").unwrap();
+        assert_eq!(Position::from_byte_offset(ok, text), Position::new(1, 6));
+        // An offset within a grapheme cluster resolves to the next column.
+        let accent = text.find('\u{301}').unwrap();
+        assert_eq!(
+            Position::from_byte_offset(accent, text),
+            Position::new(1, 5)
+        );
+        assert_eq!(Position::new(1, 6).to_byte_offset(text), Some(ok));
+    }
+
+    #[test]
+    fn to_byte_offset_is_inverse() {
+        let text = "The quick\n🦊 jumps\nthe lazy 🐕";
+        for (offset, _) in text.char_indices().chain([(text.len(), ' ')]) {
+            let position = Position::from_byte_offset(offset, text);
+            assert_eq!(position.to_byte_offset(text), Some(offset));
+        }
+    }
+
+    #[test]
+    fn to_byte_offset_out_of_bounds() {
+        let text = "The quick\n🦊 jumps";
+        assert_eq!(Position::new(0, 1).to_byte_offset(text), None);
+        assert_eq!(Position::new(1, 0).to_byte_offset(text), None);
+        assert_eq!(Position::new(3, 1).to_byte_offset(text), None);
+        assert_eq!(Position::new(2, 8).to_byte_offset(text), Some(text.len()));
+        assert_eq!(Position::new(2, 9).to_byte_offset(text), None);
+    }
+}
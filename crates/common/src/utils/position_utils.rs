@@ -1,49 +1,13 @@
 use crate::model::position::Position;
-use bstr::BStr;
-use bstr::ByteSlice;
 
 /// Get position of an offset in a code and return a [Position].
+///
+/// This is the fallible equivalent of [`Position::from_byte_offset`].
 pub fn get_position_in_string(content: &str, offset: usize) -> anyhow::Result<Position> {
     if offset >= content.len() {
         anyhow::bail!("offset is larger than content length");
     }
-
-    let bstr = BStr::new(&content);
-
-    let mut line_number: u32 = 1;
-    let lines = bstr.lines_with_terminator();
-    for line in lines {
-        let start_index = line.as_ptr() as usize - content.as_ptr() as usize;
-        let end_index = start_index + line.len();
-
-        if (start_index..end_index).contains(&offset) {
-            let mut col_number: u32 = 1;
-            for (grapheme_start, grapheme_end, _) in line.grapheme_indices() {
-                let grapheme_absolute_start = start_index + grapheme_start;
-                let grapheme_absolute_end = start_index + grapheme_end;
-
-                // It's exactly the index we are looking for.
-                if offset == grapheme_absolute_start {
-                    return Ok(Position {
-                        line: line_number,
-                        col: col_number,
-                    });
-                }
-
-                // The offset is within the grapheme we are looking for, it's the next col.
-                if (grapheme_absolute_start..grapheme_absolute_end).contains(&offset) {
-                    return Ok(Position {
-                        line: line_number,
-                        col: col_number + 1,
-                    });
-                }
-                col_number += 1;
-            }
-        }
-        line_number += 1;
-    }
-
-    Err(anyhow::anyhow!("cannot find position"))
+    Ok(Position::from_byte_offset(offset, content))
 }
 
 #[cfg(test)]
@@ -0,0 +1,257 @@
+// Unless explicitly stated otherwise all files in this repository are licensed under the Apache License, Version 2.0.
+// This product includes software developed at Datadog (https://www.datadoghq.com/).
+// Copyright 2024 Datadog, Inc.
+
+//! Baselines for the core analysis loop: parsing a file with tree-sitter, and executing rules in v8,
+//! along with micro-benchmarks for the `ddsa_lib` bridges.
+
+use common::analysis_options::AnalysisOptions;
+use criterion::{black_box, criterion_group, criterion_main, BatchSize, Criterion};
+use static_analysis_kernel::analysis::analyze::analyze_with;
+use static_analysis_kernel::analysis::ddsa_lib::v8_platform::{
+    initialize_v8, Initialized, V8Platform,
+};
+use static_analysis_kernel::analysis::tree_sitter::{get_query, get_tree};
+use static_analysis_kernel::model::common::Language;
+use static_analysis_kernel::model::rule::{RuleCategory, RuleInternal, RuleSeverity};
+use static_analysis_kernel::rule_config::RuleConfig;
+use std::sync::Arc;
+
+/// The number of lines in the generated Python file.
+const LINE_COUNT: usize = 1000;
+
+/// A rule that reports every function call.
+const SIMPLE_QUERY: &str = "(call function: (identifier) @name)";
+const SIMPLE_CODE: &str = r#"
+function visit(captures) {
+    const node = captures.get("name");
+    addError(buildError(node.start.line, node.start.col, node.end.line, node.end.col, "call"));
+}
+"#;
+
+/// A rule that inspects a function's name, parameters, and body, and walks its children.
+const COMPLEX_QUERY: &str = r#"
+(function_definition
+    name: (identifier) @name
+    parameters: (parameters) @params
+    body: (block) @body
+) @func
+"#;
+const COMPLEX_CODE: &str = r#"
+function visit(captures) {
+    const func = captures.get("func");
+    const name = captures.get("name");
+    const params = ddsa.getChildren(captures.get("params"));
+    const statements = ddsa.getChildren(captures.get("body"));
+    if (params.length > 1 && statements.length > 1 && name.text.startsWith("function_")) {
+        addError(buildError(func.start.line, func.start.col, func.end.line, func.end.col, name.text));
+    }
+}
+"#;
+
+/// The number of nodes whose range is read by the range rules.
+const RANGE_NODE_COUNT: usize = 100;
+
+/// Rules that read the full range of each function call: one through the six individual
+/// properties, and one through `sourceRange`.
+const RANGE_QUERY: &str = "(call) @call";
+const RANGE_FIELDS_CODE: &str = r#"
+function visit(captures) {
+    const node = captures.get("call");
+    const range = [node.startByte, node.endByte, node.start.line, node.start.col, node.end.line, node.end.col];
+    if (range[0] > range[1]) {
+        addError(buildError(node.start.line, node.start.col, node.end.line, node.end.col, "range"));
+    }
+}
+"#;
+const RANGE_SOURCE_RANGE_CODE: &str = r#"
+function visit(captures) {
+    const node = captures.get("call");
+    const range = node.sourceRange;
+    if (range.startByte > range.endByte) {
+        addError(buildError(range.startLine, range.startCol, range.endLine, range.endCol, "range"));
+    }
+}
+"#;
+
+/// Returns a Python file made of `function_count` small functions, each containing one call.
+/// Each function spans 4 lines.
+fn python_source(function_count: usize) -> String {
+    (0..function_count)
+        .map(|i| {
+            format!(
+                "def function_{i}(a, b):\n    total = compute(a, b)\n    return total + {i}\n\n"
+            )
+        })
+        .collect()
+}
+
+fn rule(name: &str, query: &str, code: &str) -> RuleInternal {
+    RuleInternal {
+        name: name.to_string(),
+        short_description: None,
+        description: None,
+        category: RuleCategory::BestPractices,
+        severity: RuleSeverity::Warning,
+        language: Language::Python,
+        code: code.to_string(),
+        tree_sitter_query: Some(get_query(query, &Language::Python).unwrap()),
+    }
+}
+
+/// Returns every node of the `tree`, in pre-order.
+#[cfg(feature = "bench")]
+fn all_nodes(tree: &tree_sitter::Tree) -> Vec<tree_sitter::Node> {
+    let mut nodes = Vec::new();
+    let mut stack = vec![tree.root_node()];
+    while let Some(node) = stack.pop() {
+        nodes.push(node);
+        let mut cursor = node.walk();
+        stack.extend(node.children(&mut cursor));
+    }
+    nodes
+}
+
+/// Benchmarks parsing a file and executing rules on it.
+fn analysis_benchmarks(c: &mut Criterion, v8: &V8Platform<Initialized>) {
+    let source = python_source(LINE_COUNT / 4);
+
+    c.bench_function("parse python file", |b| {
+        b.iter(|| get_tree(black_box(&source), &Language::Python).unwrap())
+    });
+
+    let mut runtime = v8.try_new_runtime().unwrap();
+    let filename: Arc<str> = Arc::from("bench.py");
+    let code: Arc<str> = Arc::from(source);
+    let rule_config = RuleConfig::default();
+    let options = AnalysisOptions::default();
+
+    for (bench_name, rule) in [
+        (
+            "simple query rule",
+            rule("bench/simple", SIMPLE_QUERY, SIMPLE_CODE),
+        ),
+        (
+            "complex multi-capture rule",
+            rule("bench/complex", COMPLEX_QUERY, COMPLEX_CODE),
+        ),
+    ] {
+        let rules = [rule];
+        c.bench_function(bench_name, |b| {
+            b.iter(|| {
+                analyze_with(
+                    &mut runtime,
+                    &Language::Python,
+                    &rules,
+                    &filename,
+                    &code,
+                    &rule_config,
+                    &options,
+                )
+            })
+        });
+    }
+}
+
+/// Measures inserting every node of a large tree into the `TsNodeBridge`, one node at a time.
+#[cfg(feature = "bench")]
+fn ts_node_bridge_benchmarks(c: &mut Criterion, v8: &V8Platform<Initialized>) {
+    let source = python_source(LINE_COUNT / 4);
+    let tree = get_tree(&source, &Language::Python).unwrap();
+    let nodes = all_nodes(&tree);
+
+    let mut runtime = v8.try_new_runtime().unwrap();
+    let bench_name = format!("ts node bridge insert ({} nodes)", nodes.len());
+    c.bench_function(&bench_name, |b| {
+        b.iter(|| runtime.bench_insert_ts_nodes(black_box(&nodes)))
+    });
+}
+
+/// Compares the latency of the first rule execution on a fresh runtime with and without a warm-up.
+fn warm_up_benchmarks(c: &mut Criterion, v8: &V8Platform<Initialized>) {
+    let filename: Arc<str> = Arc::from("bench.py");
+    let code: Arc<str> = Arc::from(python_source(LINE_COUNT / 4));
+    let rule_config = RuleConfig::default();
+    let options = AnalysisOptions::default();
+    let rules = [rule("bench/simple", SIMPLE_QUERY, SIMPLE_CODE)];
+
+    let mut group = c.benchmark_group("first rule execution");
+    for (bench_name, warm_up) in [("cold runtime", false), ("warmed-up runtime", true)] {
+        group.bench_function(bench_name, |b| {
+            b.iter_batched_ref(
+                || {
+                    if warm_up {
+                        v8.try_new_warm_runtime().unwrap()
+                    } else {
+                        v8.try_new_runtime().unwrap()
+                    }
+                },
+                |runtime| {
+                    analyze_with(
+                        runtime,
+                        &Language::Python,
+                        &rules,
+                        &filename,
+                        &code,
+                        &rule_config,
+                        &options,
+                    )
+                },
+                BatchSize::PerIteration,
+            )
+        });
+    }
+    group.finish();
+}
+
+/// Compares reading the range of [`RANGE_NODE_COUNT`] nodes through individual properties (which
+/// makes an op call for each of `startByte` and `endByte`) versus through `sourceRange` (a single op call).
+fn source_range_benchmarks(c: &mut Criterion, v8: &V8Platform<Initialized>) {
+    let mut runtime = v8.try_new_runtime().unwrap();
+    let filename: Arc<str> = Arc::from("bench.py");
+    let code: Arc<str> = Arc::from(python_source(RANGE_NODE_COUNT));
+    let rule_config = RuleConfig::default();
+    let options = AnalysisOptions::default();
+
+    let mut group = c.benchmark_group(format!("node range ({RANGE_NODE_COUNT} nodes)"));
+    for (bench_name, rule) in [
+        (
+            "individual properties",
+            rule("bench/range-fields", RANGE_QUERY, RANGE_FIELDS_CODE),
+        ),
+        (
+            "sourceRange",
+            rule("bench/source-range", RANGE_QUERY, RANGE_SOURCE_RANGE_CODE),
+        ),
+    ] {
+        let rules = [rule];
+        group.bench_function(bench_name, |b| {
+            b.iter(|| {
+                analyze_with(
+                    &mut runtime,
+                    &Language::Python,
+                    &rules,
+                    &filename,
+                    &code,
+                    &rule_config,
+                    &options,
+                )
+            })
+        });
+    }
+    group.finish();
+}
+
+fn benchmarks(c: &mut Criterion) {
+    // (v8 can only be initialized once per process).
+    let v8 = initialize_v8(0);
+    analysis_benchmarks(c, &v8);
+    // (Requires the `bench` feature, which exposes the bridge to the benchmarks).
+    #[cfg(feature = "bench")]
+    ts_node_bridge_benchmarks(c, &v8);
+    warm_up_benchmarks(c, &v8);
+    source_range_benchmarks(c, &v8);
+}
+
+criterion_group!(benches, benchmarks);
+criterion_main!(benches);
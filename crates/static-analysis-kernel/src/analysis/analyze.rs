@@ -9,9 +9,11 @@ use crate::model::analysis::{
 };
 use crate::model::common::Language;
 use crate::model::rule::{RuleCategory, RuleInternal, RuleResult, RuleSeverity};
+use crate::model::violation::SuppressedViolation;
 use crate::rule_config::RuleConfig;
-use common::analysis_options::AnalysisOptions;
+use common::analysis_options::{AnalysisOptions, RuleTimeoutAction};
 use std::borrow::Borrow;
+use std::cell::Cell;
 use std::collections::HashMap;
 use std::sync::Arc;
 use std::time::{Duration, Instant};
@@ -28,6 +30,7 @@ const DISABLE_KEYWORDS: [&str; 2] = ["no-dd-sa", "datadog-disable"];
 fn get_lines_to_ignore(code: &str, language: &Language) -> LinesToIgnore {
     let mut lines_to_ignore_for_all_rules = vec![];
     let mut lines_to_ignore_per_rules: HashMap<u32, Vec<String>> = HashMap::new();
+    let mut disabling_comment_lines: HashMap<u32, u32> = HashMap::new();
 
     let mut line_number = 1u32;
     let comment_prefixes = match language {
@@ -115,10 +118,12 @@ fn get_lines_to_ignore(code: &str, language: &Language) -> LinesToIgnore {
                     } else {
                         let mut line_to_ignore = line_number + 1;
                         lines_to_ignore_for_all_rules.push(line_to_ignore);
+                        disabling_comment_lines.insert(line_to_ignore, line_number);
 
                         while comment_lines.contains(&line_to_ignore) {
                             line_to_ignore += 1;
                             lines_to_ignore_for_all_rules.push(line_to_ignore);
+                            disabling_comment_lines.insert(line_to_ignore, line_number);
                         }
                     }
                 } else if line_number == 1 {
@@ -127,10 +132,12 @@ fn get_lines_to_ignore(code: &str, language: &Language) -> LinesToIgnore {
                     let mut line_to_ignore = line_number + 1;
                     let rule_to_ignore = parts.clone();
                     lines_to_ignore_per_rules.insert(line_to_ignore, rule_to_ignore.clone());
+                    disabling_comment_lines.insert(line_to_ignore, line_number);
 
                     while comment_lines.contains(&line_to_ignore) {
                         line_to_ignore += 1;
                         lines_to_ignore_per_rules.insert(line_to_ignore, rule_to_ignore.clone());
+                        disabling_comment_lines.insert(line_to_ignore, line_number);
                     }
                 }
             }
@@ -148,6 +155,7 @@ fn get_lines_to_ignore(code: &str, language: &Language) -> LinesToIgnore {
         lines_to_ignore: lines_to_ignore_for_all_rules,
         lines_to_ignore_per_rule: lines_to_ignore_per_rules,
         ignore_file,
+        disabling_comment_lines,
     }
 }
 
@@ -187,11 +195,14 @@ where
     let cst_parsing_time = now.elapsed();
 
     let timeout = analysis_option.timeout.or(Some(RULE_EXECUTION_TIMEOUT));
+    // Set when a rule times out and the configured action is to abort the analysis of the file.
+    let aborted = Cell::new(false);
 
-    rules
+    let results = rules
         .into_iter()
         .filter(|rule| rule_config.rule_is_enabled(&rule.borrow().name))
-        .map(|rule| {
+        .take_while(|_| !aborted.get())
+        .filter_map(|rule| {
             let rule = rule.borrow();
             if analysis_option.use_debug {
                 eprintln!("Apply rule {} file {}", rule.name, filename);
@@ -208,7 +219,14 @@ where
 
             // NOTE: This is a translation layer to map Result<T, E> to a `RuleResult` struct.
             // Eventually, `analyze` should be refactored to also use a `Result`, and then this will no longer be required.
-            let (violations, errors, execution_error, console_output, timing) = match res {
+            let (
+                violations,
+                suppressed_violations,
+                errors,
+                execution_error,
+                console_output,
+                timing,
+            ) = match res {
                 Ok(execution) => {
                     let ExecutionResult {
                         mut violations,
@@ -217,22 +235,6 @@ where
                     } = execution;
                     let console_output = (!console_lines.is_empty() && analysis_option.log_output)
                         .then_some(console_lines.join("\n"));
-                    violations.retain(|v| {
-                        let base_ignored =
-                            lines_to_ignore.should_filter_rule(rule.name.as_str(), v.start.line);
-                        // Additionally, ignore the entire flow if any of the individual regions should be ignored.
-                        let flow_ignored = v
-                            .taint_flow
-                            .as_ref()
-                            .map(|flow| {
-                                flow.iter().any(|region| {
-                                    lines_to_ignore
-                                        .should_filter_rule(rule.name.as_str(), region.start.line)
-                                })
-                            })
-                            .unwrap_or(false);
-                        !(base_ignored || flow_ignored)
-                    });
                     violations.iter_mut().for_each(|violation| {
                         if let Some(severity) = rule_config.get_severity(&rule.name) {
                             violation.severity = severity;
@@ -241,7 +243,40 @@ where
                             violation.category = category;
                         }
                     });
-                    (violations, vec![], None, console_output, timing)
+                    let mut suppressed_violations = vec![];
+                    violations.retain(|v| {
+                        let base_comment_line = lines_to_ignore
+                            .disabling_comment_line(rule.name.as_str(), v.start.line);
+                        // Additionally, ignore the entire flow if any of the individual regions should be ignored.
+                        let flow_comment_line = || {
+                            v.taint_flow.as_ref().and_then(|flow| {
+                                flow.iter().find_map(|region| {
+                                    lines_to_ignore.disabling_comment_line(
+                                        rule.name.as_str(),
+                                        region.start.line,
+                                    )
+                                })
+                            })
+                        };
+                        match base_comment_line.or_else(flow_comment_line) {
+                            Some(comment_line) => {
+                                suppressed_violations.push(SuppressedViolation {
+                                    violation: v.clone(),
+                                    comment_line,
+                                });
+                                false
+                            }
+                            None => true,
+                        }
+                    });
+                    (
+                        violations,
+                        suppressed_violations,
+                        vec![],
+                        None,
+                        console_output,
+                        timing,
+                    )
                 }
                 Err(err) => {
                     let r_f = format!("{}:{}", rule.name, filename);
@@ -255,6 +290,11 @@ where
                                     timeout.as_millis()
                                 );
                             }
+                            match analysis_option.rule_timeout_action {
+                                RuleTimeoutAction::EmitError => {}
+                                RuleTimeoutAction::Skip => return None,
+                                RuleTimeoutAction::Abort => aborted.set(true),
+                            }
                             (ERROR_RULE_TIMEOUT, None)
                         }
                         other_err => {
@@ -266,22 +306,33 @@ where
                         }
                     };
                     let errors = vec![err_kind.to_string()];
-                    (vec![], errors, execution_error, None, Default::default())
+                    (
+                        vec![],
+                        vec![],
+                        errors,
+                        execution_error,
+                        None,
+                        Default::default(),
+                    )
                 }
             };
-            RuleResult {
+            Some(RuleResult {
                 rule_name: rule.name.clone(),
                 filename: filename.to_string(),
                 violations,
+                suppressed_violations,
+                custom_tags: rule_config.get_custom_tags(&rule.name).to_vec(),
                 errors,
                 execution_error,
                 output: console_output,
                 execution_time_ms: timing.execution.as_millis(),
                 parsing_time_ms: cst_parsing_time.as_millis(),
                 query_node_time_ms: timing.ts_query.as_millis(),
-            }
+            })
         })
-        .collect()
+        .collect();
+    runtime.flush_metrics(filename);
+    results
 }
 
 /// Returns a [DOT Language] graph that models taint flow within the file.
@@ -352,7 +403,7 @@ function visit(captures) {
                 severity: RuleSeverity::None,
                 language,
                 code: rule_code.to_string(),
-                tree_sitter_query,
+                tree_sitter_query: Some(tree_sitter_query),
             };
 
             let results = analyze_with(
@@ -458,7 +509,7 @@ function visit(node, filename, code) {
             severity: RuleSeverity::Notice,
             language: Language::Python,
             code: rule_code.to_string(),
-            tree_sitter_query: get_query(QUERY_CODE, &Language::Python).unwrap(),
+            tree_sitter_query: Some(get_query(QUERY_CODE, &Language::Python).unwrap()),
         };
 
         let analysis_options = AnalysisOptions::default();
@@ -514,7 +565,7 @@ function visit(node, filename, code) {
             severity: RuleSeverity::Notice,
             language: Language::Python,
             code: rule_code1.to_string(),
-            tree_sitter_query: get_query(QUERY_CODE, &Language::Python).unwrap(),
+            tree_sitter_query: Some(get_query(QUERY_CODE, &Language::Python).unwrap()),
         };
         let rule2 = RuleInternal {
             name: "myrule2".to_string(),
@@ -524,7 +575,7 @@ function visit(node, filename, code) {
             severity: RuleSeverity::Notice,
             language: Language::Python,
             code: rule_code2.to_string(),
-            tree_sitter_query: get_query(QUERY_CODE, &Language::Python).unwrap(),
+            tree_sitter_query: Some(get_query(QUERY_CODE, &Language::Python).unwrap()),
         };
 
         let analysis_options = AnalysisOptions::default();
@@ -621,7 +672,7 @@ for(var i = 0; i <= 10; i--){}
             severity: RuleSeverity::Notice,
             language: Language::JavaScript,
             code: rule_code1.to_string(),
-            tree_sitter_query: get_query(tree_sitter_query, &Language::JavaScript).unwrap(),
+            tree_sitter_query: Some(get_query(tree_sitter_query, &Language::JavaScript).unwrap()),
         };
 
         let analysis_options = AnalysisOptions::default();
@@ -672,7 +723,7 @@ def foo():
             severity: RuleSeverity::Notice,
             language: Language::Python,
             code: rule_code1.to_string(),
-            tree_sitter_query: get_query(tree_sitter_query, &Language::Python).unwrap(),
+            tree_sitter_query: Some(get_query(tree_sitter_query, &Language::Python).unwrap()),
         };
 
         let analysis_options = AnalysisOptions::default();
@@ -719,7 +770,7 @@ def foo(arg1):
             severity: RuleSeverity::Notice,
             language: Language::Python,
             code: rule_code.to_string(),
-            tree_sitter_query: get_query(QUERY_CODE, &Language::Python).unwrap(),
+            tree_sitter_query: Some(get_query(QUERY_CODE, &Language::Python).unwrap()),
         };
 
         let analysis_options = AnalysisOptions::default();
@@ -734,6 +785,11 @@ def foo(arg1):
         assert_eq!(1, results.len());
         let result = results.get(0).unwrap();
         assert!(result.violations.is_empty());
+        // The violation is kept as suppressed, along with the line of the disabling comment.
+        assert_eq!(result.suppressed_violations.len(), 1);
+        let suppressed = &result.suppressed_violations[0];
+        assert_eq!(suppressed.violation.start.line, 3);
+        assert_eq!(suppressed.comment_line, 2);
     }
 
     #[test]
@@ -776,7 +832,7 @@ def foo2(arg1):
             severity: RuleSeverity::Notice,
             language: Language::Python,
             code: rule_code.to_string(),
-            tree_sitter_query: get_query(QUERY_CODE, &Language::Python).unwrap(),
+            tree_sitter_query: Some(get_query(QUERY_CODE, &Language::Python).unwrap()),
         };
 
         let analysis_options = AnalysisOptions::default();
@@ -829,7 +885,7 @@ function visit(captures) {
             severity: RuleSeverity::Error,
             language: Language::Java,
             code: rule_code.to_string(),
-            tree_sitter_query: get_query(ts_query, &Language::Java).unwrap(),
+            tree_sitter_query: Some(get_query(ts_query, &Language::Java).unwrap()),
         };
 
         let analysis_options = AnalysisOptions::default();
@@ -998,7 +1054,7 @@ function visit(node, filename, code) {
             severity: RuleSeverity::Notice,
             language: Language::Go,
             code: rule_code.to_string(),
-            tree_sitter_query: get_query(query, &Language::Go).unwrap(),
+            tree_sitter_query: Some(get_query(query, &Language::Go).unwrap()),
         };
 
         let analysis_options = AnalysisOptions {
@@ -1154,7 +1210,7 @@ function visit(node, filename, code) {
             severity: RuleSeverity::Notice,
             language: Language::Python,
             code: rule_code.to_string(),
-            tree_sitter_query: get_query(QUERY_CODE, &Language::Python).unwrap(),
+            tree_sitter_query: Some(get_query(QUERY_CODE, &Language::Python).unwrap()),
         };
         let rule2 = RuleInternal {
             name: "rs/rule2".to_string(),
@@ -1164,7 +1220,7 @@ function visit(node, filename, code) {
             severity: RuleSeverity::Notice,
             language: Language::Python,
             code: rule_code.to_string(),
-            tree_sitter_query: get_query(QUERY_CODE, &Language::Python).unwrap(),
+            tree_sitter_query: Some(get_query(QUERY_CODE, &Language::Python).unwrap()),
         };
 
         let analysis_options = AnalysisOptions::default();
@@ -1224,7 +1280,7 @@ function visit(query, filename, code) {
             severity: RuleSeverity::Notice,
             language: Language::Starlark,
             code: rule_code.to_string(),
-            tree_sitter_query: get_query(QUERY_CODE, &Language::Starlark).unwrap(),
+            tree_sitter_query: Some(get_query(QUERY_CODE, &Language::Starlark).unwrap()),
         };
 
         let analysis_options = AnalysisOptions::default();
@@ -1270,7 +1326,7 @@ function visit(node, filename, code) {
             severity: RuleSeverity::Notice,
             language: Language::Python,
             code: rule_code.to_string(),
-            tree_sitter_query: get_query(QUERY_CODE, &Language::Python).unwrap(),
+            tree_sitter_query: Some(get_query(QUERY_CODE, &Language::Python).unwrap()),
         };
         let rule2 = RuleInternal {
             name: "rs/rule2".to_string(),
@@ -1280,7 +1336,7 @@ function visit(node, filename, code) {
             severity: RuleSeverity::Notice,
             language: Language::Python,
             code: rule_code.to_string(),
-            tree_sitter_query: get_query(QUERY_CODE, &Language::Python).unwrap(),
+            tree_sitter_query: Some(get_query(QUERY_CODE, &Language::Python).unwrap()),
         };
 
         let analysis_options = AnalysisOptions {
@@ -1288,6 +1344,8 @@ function visit(node, filename, code) {
             use_debug: false,
             ignore_generated_files: false,
             timeout: None,
+            rule_timeout_action: RuleTimeoutAction::default(),
+            source_map_directory: None,
         };
         let rule_config_provider = RuleConfigProvider::from_config(
             &parse_config_file(
@@ -1377,6 +1435,118 @@ rulesets:
         );
     }
 
+    #[test]
+    fn test_global_rule_overrides() {
+        let rule_code = r#"
+function visit(node, filename, code) {
+    const functionName = node.captures["name"];
+    const error = buildError(
+        functionName.start.line, functionName.start.col,
+        functionName.end.line, functionName.end.col,
+        `error`);
+    addError(error);
+}
+        "#;
+        let rule = |name: &str| RuleInternal {
+            name: name.to_string(),
+            short_description: None,
+            description: None,
+            category: RuleCategory::CodeStyle,
+            severity: RuleSeverity::Notice,
+            language: Language::Python,
+            code: rule_code.to_string(),
+            tree_sitter_query: Some(get_query(QUERY_CODE, &Language::Python).unwrap()),
+        };
+        let rules = vec![rule("rs/rule1"), rule("rs/rule2"), rule("rs/rule3")];
+        let rule_config_provider = RuleConfigProvider::from_config(
+            &parse_config_file(
+                r#"
+rulesets:
+  - rs:
+    rules:
+      rule1:
+        severity: ERROR
+global-rule-overrides:
+  rs/rule1:
+    severity: WARNING
+  rs/rule2:
+    severity: WARNING
+    category: SECURITY
+  rs/rule3:
+    enabled: false
+        "#,
+            )
+            .unwrap(),
+        );
+
+        let results = analyze(
+            &Language::Python,
+            &rules,
+            &Arc::from("myfile.py"),
+            &Arc::from(PYTHON_CODE),
+            &rule_config_provider.config_for_file("myfile.py"),
+            &AnalysisOptions::default(),
+        );
+        assert_eq!(results.len(), 2);
+        // The rule-level override takes precedence over the global one.
+        assert_eq!(results[0].violations[0].severity, RuleSeverity::Error);
+        assert_eq!(results[0].violations[0].category, RuleCategory::CodeStyle);
+        assert_eq!(results[1].rule_name, "rs/rule2");
+        assert_eq!(results[1].violations[0].severity, RuleSeverity::Warning);
+        assert_eq!(results[1].violations[0].category, RuleCategory::Security);
+    }
+
+    #[test]
+    fn test_rule_timeout_action() {
+        let rule = |name: &str, code: &str| RuleInternal {
+            name: name.to_string(),
+            short_description: None,
+            description: None,
+            category: RuleCategory::CodeStyle,
+            severity: RuleSeverity::Notice,
+            language: Language::Python,
+            code: code.to_string(),
+            tree_sitter_query: Some(get_query(QUERY_CODE, &Language::Python).unwrap()),
+        };
+        let rules = vec![
+            rule(
+                "rs/slow-rule",
+                "function visit(node, filename, code) { while (true) {} }",
+            ),
+            rule("rs/fast-rule", "function visit(node, filename, code) {}"),
+        ];
+        let run = |rule_timeout_action: RuleTimeoutAction| {
+            let analysis_options = AnalysisOptions {
+                timeout: Some(Duration::from_millis(100)),
+                rule_timeout_action,
+                ..Default::default()
+            };
+            analyze(
+                &Language::Python,
+                &rules,
+                &Arc::from("myfile.py"),
+                &Arc::from(PYTHON_CODE),
+                &RuleConfig::default(),
+                &analysis_options,
+            )
+        };
+
+        let results = run(RuleTimeoutAction::EmitError);
+        assert_eq!(results.len(), 2);
+        assert_eq!(results[0].errors, vec![ERROR_RULE_TIMEOUT.to_string()]);
+        assert!(results[1].errors.is_empty());
+
+        let results = run(RuleTimeoutAction::Skip);
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].rule_name, "rs/fast-rule");
+        assert!(results[0].errors.is_empty());
+
+        let results = run(RuleTimeoutAction::Abort);
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].rule_name, "rs/slow-rule");
+        assert_eq!(results[0].errors, vec![ERROR_RULE_TIMEOUT.to_string()]);
+    }
+
     #[test]
     fn java_taint_flow_dot_graph() {
         // language=java
@@ -8,6 +8,7 @@ pub mod context;
 pub use context::*;
 pub mod extension;
 pub(crate) mod js;
+pub mod metrics;
 pub(crate) mod ops;
 pub(crate) mod resource_watchdog;
 pub(crate) mod runtime;
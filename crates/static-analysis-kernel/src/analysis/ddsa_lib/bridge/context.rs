@@ -3,11 +3,13 @@
 // Copyright 2024 Datadog, Inc.
 
 use crate::analysis::ddsa_lib;
-use crate::analysis::ddsa_lib::common::{DDSAJsRuntimeError, Instance};
+use crate::analysis::ddsa_lib::common::{v8_string, DDSAJsRuntimeError, Instance};
 use crate::analysis::ddsa_lib::js;
+use crate::analysis::tree_sitter::TSQuery;
 use crate::model::common::Language;
 use deno_core::v8;
 use deno_core::v8::HandleScope;
+use std::collections::HashMap;
 use std::ops::Deref;
 use std::sync::Arc;
 
@@ -25,6 +27,12 @@ pub struct ContextBridge {
     rule: Linked<ddsa_lib::RuleContext, js::RuleContext<Instance>>,
     file: Linked<ddsa_lib::FileContext, js::FileContext<Instance>>,
     ts_lang: Linked<ddsa_lib::TsLanguageContext, js::TsLanguageContext<Instance>>,
+    /// A plain object containing per-file variables injected by the caller (see [`Self::set_scope_variables`]).
+    scope_vars: v8::Global<v8::Object>,
+    /// A map from a tree-sitter query string to its compiled [`TSQuery`], for queries run at JavaScript runtime.
+    ts_query_cache: HashMap<String, Arc<TSQuery>>,
+    /// The byte offset at which each line of the current file starts.
+    line_offsets: Vec<usize>,
 }
 
 impl ContextBridge {
@@ -68,12 +76,17 @@ impl ContextBridge {
         root.js.set_rule_ctx(scope, Some(&rule.js));
         root.js.set_file_ctx(scope, Some(&file.js));
         root.js.set_ts_lang_ctx(scope, Some(&ts_lang.js));
+        let scope_vars = v8::Object::new(scope);
+        let scope_vars = v8::Global::new(scope, scope_vars);
 
         Ok(Self {
             root,
             rule,
             file,
             ts_lang,
+            scope_vars,
+            ts_query_cache: HashMap::new(),
+            line_offsets: Vec::new(),
         })
     }
 
@@ -82,6 +95,37 @@ impl ContextBridge {
         self.root.js.as_local(scope)
     }
 
+    /// Returns a local handle to the [`v8::Global`] object containing the scope variables.
+    pub fn scope_vars_as_local<'s>(
+        &self,
+        scope: &mut HandleScope<'s>,
+    ) -> v8::Local<'s, v8::Object> {
+        v8::Local::new(scope, &self.scope_vars)
+    }
+
+    /// Replaces the per-file variables exposed to JavaScript (as `DD_FILE_CONTEXT`) with the
+    /// key-value pairs of the provided map. Any variables from a previous call are removed.
+    ///
+    /// NOTE: These are exposed as properties of a single object, so variable names won't shadow
+    /// existing globals. However, the `DD_FILE_CONTEXT` identifier itself must not collide with
+    /// a global defined by a rule.
+    pub fn set_scope_variables(&mut self, scope: &mut HandleScope, vars: &HashMap<String, String>) {
+        let v8_scope_vars = self.scope_vars.open(scope);
+        if let Some(existing_keys) = v8_scope_vars.get_own_property_names(scope, Default::default())
+        {
+            for i in 0..existing_keys.length() {
+                if let Some(key) = existing_keys.get_index(scope, i) {
+                    v8_scope_vars.delete(scope, key);
+                }
+            }
+        }
+        for (name, value) in vars {
+            let v8_key = v8_string(scope, name);
+            let v8_value = v8_string(scope, value);
+            v8_scope_vars.set(scope, v8_key.into(), v8_value.into());
+        }
+    }
+
     /// Assigns the provided metadata to the context.
     ///
     /// Returns `true` if the incoming `tree` was different from the last one analyzed, or `false`
@@ -93,6 +137,8 @@ impl ContextBridge {
         file_contents: &Arc<str>,
         filename: &Arc<str>,
     ) -> bool {
+        // Each rule execution gets its own copy of the line offsets.
+        self.root.js.clear_line_offsets_cache(scope);
         let mut was_new_tree = false;
         let mut was_new_ts_lang = false;
         // NOTE:
@@ -123,6 +169,8 @@ impl ContextBridge {
             // This really has no performance impact, as the number of times we'll change languages
             // has an upper bound of the count of [`crate::model::common::Language`] variants.
             self.clear_file_contexts(scope);
+            // Compiled queries are specific to a tree-sitter Language.
+            self.ts_query_cache.clear();
         }
         // Because trees and file contents go hand-in-hand, we can avoid a relatively expensive string
         // comparison by just using the `new_tree` boolean for control flow.
@@ -130,6 +178,7 @@ impl ContextBridge {
             self.root.ddsa.set_text(Arc::clone(file_contents));
             // The cache is populated lazily, so a change in value means we need to clear the cache.
             self.root.js.set_file_contents_cache(scope, None);
+            self.set_line_offsets(scope, compute_line_offsets(file_contents));
         }
         if self.root.ddsa.get_filename() != Some(filename.as_ref()) {
             self.root.ddsa.set_filename(Arc::clone(filename));
@@ -139,6 +188,43 @@ impl ContextBridge {
         was_new_tree
     }
 
+    /// Removes all metadata from the context, as if no file had been analyzed yet.
+    ///
+    /// This is only required when a scan is abandoned partway through a file: when switching files,
+    /// [`Self::set_root_context`] already replaces the previous file's metadata.
+    pub fn clear(&mut self, scope: &mut HandleScope) {
+        self.root.ddsa.clear();
+        self.root.js.set_file_contents_cache(scope, None);
+        self.root.js.set_filename_cache(scope, None);
+        self.rule.ddsa.clear_arguments(scope);
+        self.rule.ddsa.set_rule_id(None);
+        self.ts_lang.js.set_metadata(scope, None, None);
+        self.clear_file_contexts(scope);
+        self.ts_query_cache.clear();
+        self.set_scope_variables(scope, &HashMap::new());
+        self.set_line_offsets(scope, Vec::new());
+    }
+
+    /// Returns the byte offset at which each line of the current file starts.
+    pub fn file_line_offsets(&self) -> &[usize] {
+        &self.line_offsets
+    }
+
+    /// Stores the line offsets and mirrors them to the JavaScript `DD_LINE_OFFSETS` array
+    /// (which is `undefined` when there are none).
+    fn set_line_offsets(&mut self, scope: &mut HandleScope, line_offsets: Vec<usize>) {
+        if line_offsets.is_empty() {
+            self.root.js.set_line_offsets(scope, None);
+        } else {
+            let v8_offsets = line_offsets
+                .iter()
+                .map(|&offset| offset as u32)
+                .collect::<Vec<_>>();
+            self.root.js.set_line_offsets(scope, Some(&v8_offsets));
+        }
+        self.line_offsets = line_offsets;
+    }
+
     /// Assigns the provide rule arguments to the context.
     pub fn set_rule_arguments<K: Into<String>, V: Into<String>>(
         &mut self,
@@ -151,6 +237,11 @@ impl ContextBridge {
         }
     }
 
+    /// Sets the id of the rule that is about to execute, or clears it if `None` is passed in.
+    pub fn set_rule_id(&mut self, rule_id: Option<&str>) {
+        self.rule.ddsa.set_rule_id(rule_id);
+    }
+
     /// Updates the file context for the specific `Language`.
     ///
     /// NOTE: It's up to the caller to ensure [`Self::clear_file_contexts`] has been called,
@@ -197,6 +288,26 @@ impl ContextBridge {
         &self.root.ddsa
     }
 
+    /// Returns the compiled [`TSQuery`] for the provided query string, compiling it for the current
+    /// tree's language and caching it if it hasn't been seen before.
+    ///
+    /// Returns `None` if there is no tree, or if the query is invalid.
+    pub(crate) fn ts_query(&mut self, query: &str) -> Option<Arc<TSQuery>> {
+        if let Some(ts_query) = self.ts_query_cache.get(query) {
+            return Some(Arc::clone(ts_query));
+        }
+        let tree = self.root.ddsa.get_tree()?;
+        let ts_query = Arc::new(TSQuery::try_new(&tree.language(), query).ok()?);
+        self.ts_query_cache
+            .insert(query.to_string(), Arc::clone(&ts_query));
+        Some(ts_query)
+    }
+
+    /// Returns a reference to the underlying `ddsa_lib::RuleContext`.
+    pub(crate) fn ddsa_rule_context(&self) -> &ddsa_lib::RuleContext {
+        &self.rule.ddsa
+    }
+
     /// Initializes all file contexts supported by the associated [`ddsa_lib::FileContext`].
     fn init_all_file_ctx(
         scope: &mut HandleScope,
@@ -227,13 +338,20 @@ impl ContextBridge {
     }
 }
 
+/// Returns the byte offset at which each line of `text` starts. The first line always starts at `0`.
+fn compute_line_offsets(text: &str) -> Vec<usize> {
+    std::iter::once(0)
+        .chain(text.match_indices('\n').map(|(idx, _)| idx + 1))
+        .collect()
+}
+
 #[cfg(test)]
 mod tests {
     use crate::analysis::ddsa_lib::bridge::ContextBridge;
-    use crate::analysis::ddsa_lib::common::v8_string;
+    use crate::analysis::ddsa_lib::common::{compile_script, v8_string};
     use crate::analysis::ddsa_lib::test_utils::{
-        attach_as_global, cfg_test_v8, format_ts_lang_pointer, parse_code, try_execute,
-        KEY_TS_LANGUAGE_PTR,
+        attach_as_global, cfg_test_v8, format_ts_lang_pointer, parse_code, shorthand_execute_rule,
+        try_execute, KEY_TS_LANGUAGE_PTR,
     };
     use crate::analysis::tree_sitter::get_tree;
     use crate::model::common::Language;
@@ -295,6 +413,81 @@ mod tests {
         assert!(bridge.root.js.get_filename_cache(scope).is_none());
     }
 
+    /// `clear` removes the file metadata, so `op_current_ts_tree_text` no longer returns the file contents.
+    #[test]
+    fn clear_removes_file_metadata() {
+        let mut rt = cfg_test_v8().new_runtime();
+        let text = "const abc = 123;";
+        let rule_code = "function visit(captures) {}";
+        shorthand_execute_rule(
+            &mut rt,
+            Language::JavaScript,
+            "(identifier) @cap",
+            rule_code,
+            text,
+            None,
+        )
+        .unwrap();
+        let bridge = rt.bridge_context();
+        assert_eq!(bridge.borrow().ddsa_root_context().get_text(), Some(text));
+
+        bridge.borrow_mut().clear(&mut rt.v8_handle_scope());
+        assert!(bridge.borrow().ddsa_root_context().get_text().is_none());
+        assert!(bridge.borrow().ddsa_root_context().get_filename().is_none());
+        assert!(bridge.borrow().ddsa_root_context().get_tree().is_none());
+        assert!(bridge.borrow().ddsa_rule_context().get_rule_id().is_none());
+
+        let code = "\
+const text = Deno.core.ops.op_current_ts_tree_text();
+let err;
+try {
+    globalThis.__RUST_BRIDGE__context.fileContents;
+} catch (e) {
+    err = e.message;
+}
+`${text}|${err}`;
+";
+        let script = compile_script(&mut rt.v8_handle_scope(), code).unwrap();
+        let value = rt
+            .scoped_execute(&script, |s, v| v.to_rust_string_lossy(s), None)
+            .unwrap();
+        assert_eq!(
+            value,
+            "null|file contents are not available outside of a rule execution"
+        );
+    }
+
+    /// `set_root_context` precomputes the start offset of each line and exposes it as `DD_LINE_OFFSETS`.
+    #[test]
+    fn set_root_context_line_offsets() {
+        let mut rt = cfg_test_v8().new_runtime();
+        let text = "\
+const abc = 123;
+const def = 456;
+
+const ghi = 789;";
+        let rule_code = r#"
+function visit(captures) {
+    console.log(DD_LINE_OFFSETS instanceof Uint32Array, Array.from(DD_LINE_OFFSETS).join(","));
+}
+"#;
+        let res = shorthand_execute_rule(
+            &mut rt,
+            Language::JavaScript,
+            "(program) @cap",
+            rule_code,
+            text,
+            None,
+        )
+        .unwrap();
+        assert_eq!(res.console_lines[0], "true 0,17,34,35");
+        let bridge = rt.bridge_context();
+        assert_eq!(bridge.borrow().file_line_offsets(), &[0, 17, 34, 35]);
+
+        bridge.borrow_mut().clear(&mut rt.v8_handle_scope());
+        assert!(bridge.borrow().file_line_offsets().is_empty());
+    }
+
     /// Ensures `set_rule_arguments` updates the JavaScript map, and that sequential calls don't co-mingle arguments.
     #[rustfmt::skip]
     #[test]
@@ -325,6 +518,30 @@ mod tests {
         }
     }
 
+    /// Ensures `set_scope_variables` updates the `DD_FILE_CONTEXT` object, and that sequential calls don't co-mingle variables.
+    #[rustfmt::skip]
+    #[test]
+    fn set_scope_variables_is_exact() {
+        let mut runtime = cfg_test_v8().deno_core_rt();
+        let scope = &mut runtime.handle_scope();
+        let mut bridge = ContextBridge::try_new(scope).unwrap();
+        let v8_scope_vars = bridge.scope_vars_as_local(scope);
+        attach_as_global(scope, v8_scope_vars, "DD_FILE_CONTEXT");
+
+        let vars = HashMap::from([
+            ("projectName".to_string(), "my-project".to_string()),
+            ("filePath".to_string(), "src/main.js".to_string()),
+        ]);
+        bridge.set_scope_variables(scope, &vars);
+        let value = try_execute(scope, "`${DD_FILE_CONTEXT.projectName}:${DD_FILE_CONTEXT.filePath}`;").unwrap();
+        assert_eq!(value.to_rust_string_lossy(scope), "my-project:src/main.js");
+
+        let vars = HashMap::from([("projectName".to_string(), "other-project".to_string())]);
+        bridge.set_scope_variables(scope, &vars);
+        let value = try_execute(scope, "`${DD_FILE_CONTEXT.projectName}:${DD_FILE_CONTEXT.filePath}`;").unwrap();
+        assert_eq!(value.to_rust_string_lossy(scope), "other-project:undefined");
+    }
+
     /// Tests that the tree-sitter language context is updated when the `RootContext` is set.
     #[test]
     fn set_root_context_ts_lang() {
@@ -354,6 +571,43 @@ mod tests {
         assert_ts_lang_ctx(scope, &bridge, tree_2.language().deref());
     }
 
+    /// Tests that compiled queries are cached by query string, and that the cache is cleared
+    /// when the tree-sitter language changes.
+    #[test]
+    fn ts_query_cache() {
+        let mut runtime = cfg_test_v8().deno_core_rt();
+        let scope = &mut runtime.handle_scope();
+        let mut bridge = ContextBridge::try_new(scope).unwrap();
+        // Queries can't be compiled without a tree.
+        assert!(bridge.ts_query("(identifier) @id").is_none());
+
+        let contents_1: Arc<str> = Arc::from("const abc = 123;");
+        let filename_1: Arc<str> = Arc::from("11111.js");
+        let tree_1 = Arc::new(parse_code(contents_1.as_ref(), Language::JavaScript));
+        bridge.set_root_context(scope, &tree_1, &contents_1, &filename_1);
+        let first = bridge.ts_query("(identifier) @id").unwrap();
+        let second = bridge.ts_query("(identifier) @id").unwrap();
+        assert!(Arc::ptr_eq(&first, &second));
+        assert!(bridge.ts_query("(identifier").is_none());
+        assert_eq!(bridge.ts_query_cache.len(), 1);
+
+        // A tree with the same language keeps the cache.
+        let contents_2: Arc<str> = Arc::from("const def = 456;");
+        let filename_2: Arc<str> = Arc::from("22222.js");
+        let tree_2 = Arc::new(parse_code(contents_2.as_ref(), Language::JavaScript));
+        bridge.set_root_context(scope, &tree_2, &contents_2, &filename_2);
+        assert_eq!(bridge.ts_query_cache.len(), 1);
+
+        // A tree with a different language clears it.
+        let contents_3: Arc<str> = Arc::from("ghi = 789");
+        let filename_3: Arc<str> = Arc::from("33333.py");
+        let tree_3 = Arc::new(parse_code(contents_3.as_ref(), Language::Python));
+        bridge.set_root_context(scope, &tree_3, &contents_3, &filename_3);
+        assert!(bridge.ts_query_cache.is_empty());
+        let third = bridge.ts_query("(identifier) @id").unwrap();
+        assert!(!Arc::ptr_eq(&first, &third));
+    }
+
     #[rustfmt::skip]
     #[test]
     /// Tests that go module aliases are eagerly calculated by calling `set_file_context`.
@@ -134,8 +134,7 @@ impl TsNodeBridge {
     }
 
     /// Looks up a value in the v8_map, returning the value as a v8 object.
-    #[cfg(test)]
-    fn v8_get<'s>(
+    pub fn v8_get<'s>(
         &self,
         scope: &mut HandleScope<'s>,
         id: NodeId,
@@ -157,6 +156,7 @@ mod tests {
         attach_as_global, cfg_test_v8, try_execute, TsTree,
     };
     use crate::analysis::ddsa_lib::RawTSNode;
+    use crate::analysis::tree_sitter::NodePositionExt;
     use crate::model::common::Language;
     use deno_core::v8;
     use deno_core::v8::HandleScope;
@@ -176,10 +176,11 @@ mod tests {
         };
         // We intentionally do not check `id` here because that is our abstraction, not tree-sitter's.
         // Because we also normalize the `tree_sitter::Point` to be 1-based, we need to do that here.
-        equals("_startLine", node.start_position().row + 1)
-            && equals("_startCol", node.start_position().column + 1)
-            && equals("_endLine", node.end_position().row + 1)
-            && equals("_endCol", node.end_position().column + 1)
+        let (start, end) = (node.start_position_1based(), node.end_position_1based());
+        equals("_startLine", start.line as usize)
+            && equals("_startCol", start.col as usize)
+            && equals("_endLine", end.line as usize)
+            && equals("_endCol", end.col as usize)
             && equals("_typeId", node.grammar_id() as usize)
     }
 
@@ -72,6 +72,14 @@ impl RootContext {
         Option::replace(&mut self.filename, filename)
     }
 
+    /// Removes the tree, text, and filename from the context.
+    pub fn clear(&mut self) {
+        self.parent_map.borrow_mut().clear();
+        self.tree = None;
+        self.tree_text = None;
+        self.filename = None;
+    }
+
     /// Returns the parent node of the provided child.
     ///
     /// # Returns
@@ -12,12 +12,17 @@ use deno_core::v8::HandleScope;
 pub struct RuleContext {
     /// A mapping from argument names to values
     arguments: MirroredIndexMap<String, String>,
+    /// The id of the rule that is currently executing.
+    rule_id: Option<String>,
 }
 
 impl RuleContext {
     pub fn new(scope: &mut HandleScope) -> Self {
         let arguments = MirroredIndexMap::<String, String>::new(scope);
-        RuleContext { arguments }
+        RuleContext {
+            arguments,
+            rule_id: None,
+        }
     }
 
     /// Inserts an argument name and value pair
@@ -42,6 +47,16 @@ impl RuleContext {
         self.arguments.clear(scope);
     }
 
+    /// Sets the id of the rule that is currently executing, or clears it if `None` is passed in.
+    pub fn set_rule_id(&mut self, rule_id: Option<&str>) {
+        self.rule_id = rule_id.map(ToString::to_string);
+    }
+
+    /// Returns the id of the rule that is currently executing, if any.
+    pub fn get_rule_id(&self) -> Option<&str> {
+        self.rule_id.as_deref()
+    }
+
     /// Returns a reference to the [`v8::Global`] arguments map
     pub fn arguments_map(&self) -> &v8::Global<v8::Map> {
         self.arguments.v8_map()
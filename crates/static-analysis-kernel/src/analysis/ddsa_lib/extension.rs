@@ -7,12 +7,23 @@ use crate::analysis::ddsa_lib::ops;
 deno_core::extension!(
     ddsa_lib,
     ops = [
+        ops::op_compile_ts_query,
         ops::op_current_filename,
+        ops::op_current_rule_id,
         ops::op_console_push,
         ops::op_current_ts_tree_text,
+        ops::op_report_metric,
+        ops::op_ts_node_by_path,
+        ops::op_ts_node_end_byte,
         ops::op_ts_node_named_children,
         ops::op_ts_node_parent,
+        ops::op_ts_node_sibling_count,
+        ops::op_ts_node_source_range,
+        ops::op_ts_node_start_byte,
         ops::op_ts_node_text,
+        ops::op_ts_node_utf8_text_bytes,
+        ops::op_ts_query_matches,
+        ops::op_ts_query_node_matches,
         // Language-specific
         ops::op_java_get_bin_expr_operator,
         ops::op_digraph_adjacency_list_to_dot,
@@ -31,6 +42,7 @@ deno_core::extension!(
         "ext:ddsa_lib/ddsa" = "ddsa.js",
         "ext:ddsa_lib/edit" = "edit.js",
         "ext:ddsa_lib/fix" = "fix.js",
+        "ext:ddsa_lib/metric" = "metric.js",
         "ext:ddsa_lib/flow/graph" = "flow/graph.js",
         "ext:ddsa_lib/flow/java" = "flow/java.js",
         "ext:ddsa_lib/query_match" = "query_match.js",
@@ -39,6 +51,7 @@ deno_core::extension!(
         "ext:ddsa_lib/stella_compat" = "stella_compat.js",
         "ext:ddsa_lib/utility" = "utility.js",
         "ext:ddsa_lib/ts_node" = "ts_node.js",
+        "ext:ddsa_lib/ts_query" = "ts_query.js",
         "ext:ddsa_lib/violation" = "violation.js",
     ],
 );
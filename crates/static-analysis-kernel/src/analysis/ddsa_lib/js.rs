@@ -35,6 +35,8 @@ mod region;
 pub(crate) use region::CodeRegion;
 mod ts_node;
 pub(crate) use ts_node::*;
+mod ts_query;
+pub(crate) use ts_query::*;
 mod stella_compat;
 pub(crate) use stella_compat::*;
 mod utility;
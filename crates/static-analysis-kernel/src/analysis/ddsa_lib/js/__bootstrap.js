@@ -13,11 +13,13 @@ import {FileContextGo} from "ext:ddsa_lib/context_file_go";
 import {FileContextTerraform, TerraformResource} from "ext:ddsa_lib/context_file_tf";
 import {FileContextJavaScript, PackageImport} from "ext:ddsa_lib/context_file_js";
 import {Fix} from "ext:ddsa_lib/fix";
+import {reportMetric} from "ext:ddsa_lib/metric";
 import {QueryMatch} from "ext:ddsa_lib/query_match";
 import {QueryMatchCompat} from "ext:ddsa_lib/query_match_compat";
 import {RootContext} from "ext:ddsa_lib/context_root";
 import {RuleContext} from "ext:ddsa_lib/context_rule";
 import {TreeSitterFieldChildNode, TreeSitterNode} from "ext:ddsa_lib/ts_node";
+import {TreeSitterQuery} from "ext:ddsa_lib/ts_query";
 import {TsLanguageContext} from "ext:ddsa_lib/context_ts_lang";
 import {Violation} from "ext:ddsa_lib/violation";
 // TODO(JF): These are only used by the Rust runtime, which currently expects them in global scope, but
@@ -55,3 +57,14 @@ globalThis.console = new DDSA_Console();
 globalThis.ddsa = new DDSA();
 // Note: The name "private" is just used to communicate intent -- there is no enforcement preventing rules from using this.
 globalThis.__ddsaPrivate__ = new DDSAPrivate();
+globalThis.reportMetric = reportMetric;
+globalThis.TreeSitterQuery = TreeSitterQuery;
+// The start byte offset of each line of the file being analyzed. Rules can binary-search this array
+// to convert a byte offset to a line without a call into Rust.
+Object.defineProperty(globalThis, "DD_LINE_OFFSETS", {
+    get() {
+        return globalThis.__RUST_BRIDGE__context.lineOffsets;
+    },
+    enumerable: false,
+    configurable: false,
+});
@@ -31,6 +31,20 @@ export class RootContext {
          * @private
          */
         this.__js_cachedFileContents = undefined;
+        /**
+         * The byte offset at which each line of the file starts, set by Rust whenever the file changes.
+         * This is shared by every rule execution on the file, so it is intended to be an internal field.
+         * External callers should use the {@link RootContext.lineOffsets} getter.
+         * @type {Uint32Array | undefined}
+         * @private
+         */
+        this.__js_lineOffsets = undefined;
+        /**
+         * The current rule execution's copy of the line offsets. This is cleared by Rust before each execution.
+         * @type {Uint32Array | undefined}
+         * @private
+         */
+        this.__js_cachedLineOffsets = undefined;
         /**
          * An object that provides extra metadata for a specific filetypes.
          * @type {FileContext}
@@ -57,11 +71,31 @@ export class RootContext {
      */
     get fileContents() {
         if (this.__js_cachedFileContents === undefined) {
-            this.__js_cachedFileContents = op_current_ts_tree_text();
+            const fileContents = op_current_ts_tree_text();
+            if (fileContents === null) {
+                throw new Error("file contents are not available outside of a rule execution");
+            }
+            this.__js_cachedFileContents = fileContents;
         }
         return this.__js_cachedFileContents;
     }
 
+    /**
+     * A getter for the byte offset at which each line of the file starts (the first entry is always `0`).
+     * This is exposed to rules as the `DD_LINE_OFFSETS` global.
+     *
+     * @remarks
+     * The offsets are copied once per rule execution, so a rule that mutates the array can't affect other rules.
+     *
+     * @returns {Uint32Array | undefined}
+     */
+    get lineOffsets() {
+        if (this.__js_cachedLineOffsets === undefined && this.__js_lineOffsets !== undefined) {
+            this.__js_cachedLineOffsets = this.__js_lineOffsets.slice();
+        }
+        return this.__js_cachedLineOffsets;
+    }
+
     /**
      * A getter for the name of the file.
      *
@@ -21,6 +21,8 @@ pub struct RootContext<T> {
     s_ts_lang_ctx: v8::Global<v8::String>,
     s_filename: v8::Global<v8::String>,
     s_file_contents: v8::Global<v8::String>,
+    s_line_offsets: v8::Global<v8::String>,
+    s_line_offsets_cache: v8::Global<v8::String>,
     /// (See documentation on [`Instance`]).
     _pd: PhantomData<T>,
 }
@@ -49,6 +51,10 @@ impl RootContext<Instance> {
         let s_filename = v8::Global::new(scope, s_filename);
         let s_file_contents = v8_interned(scope, "__js_cachedFileContents");
         let s_file_contents = v8::Global::new(scope, s_file_contents);
+        let s_line_offsets = v8_interned(scope, "__js_lineOffsets");
+        let s_line_offsets = v8::Global::new(scope, s_line_offsets);
+        let s_line_offsets_cache = v8_interned(scope, "__js_cachedLineOffsets");
+        let s_line_offsets_cache = v8::Global::new(scope, s_line_offsets_cache);
         Ok(Self {
             v8_object,
             s_file_ctx,
@@ -56,6 +62,8 @@ impl RootContext<Instance> {
             s_ts_lang_ctx,
             s_filename,
             s_file_contents,
+            s_line_offsets,
+            s_line_offsets_cache,
             _pd: PhantomData,
         })
     }
@@ -144,6 +152,32 @@ impl RootContext<Instance> {
         }
     }
 
+    /// Sets the line start offsets of the file as a `Uint32Array`, clearing the current execution's copy.
+    pub fn set_line_offsets(&self, scope: &mut HandleScope, line_offsets: Option<&[u32]>) {
+        self.clear_line_offsets_cache(scope);
+        if let Some(line_offsets) = line_offsets {
+            set_key_value(&self.v8_object, scope, &self.s_line_offsets, |inner| {
+                let bytes = line_offsets
+                    .iter()
+                    .flat_map(|offset| offset.to_ne_bytes())
+                    .collect::<Vec<_>>();
+                let backing_store =
+                    v8::ArrayBuffer::new_backing_store_from_vec(bytes).make_shared();
+                let array_buffer = v8::ArrayBuffer::with_backing_store(inner, &backing_store);
+                v8::Uint32Array::new(inner, array_buffer, 0, line_offsets.len())
+                    .expect("array buffer should fit the offsets")
+                    .into()
+            });
+        } else {
+            set_undefined(&self.v8_object, scope, &self.s_line_offsets);
+        }
+    }
+
+    /// Clears the current rule execution's copy of the line start offsets.
+    pub fn clear_line_offsets_cache(&self, scope: &mut HandleScope) {
+        set_undefined(&self.v8_object, scope, &self.s_line_offsets_cache);
+    }
+
     #[cfg(test)]
     fn get_cache(&self, scope: &mut HandleScope, key: &v8::Global<v8::String>) -> Option<String> {
         let v8_key = v8::Local::new(scope, key);
@@ -189,11 +223,14 @@ mod tests {
             // Variables
             "__js_cachedFilename",
             "__js_cachedFileContents",
+            "__js_lineOffsets",
+            "__js_cachedLineOffsets",
             "fileCtx",
             "ruleCtx",
             "tsLangCtx",
             // Methods
             "fileContents",
+            "lineOffsets",
             "filename",
         ];
         assert!(js_instance_eq(RootContext::CLASS_NAME, instance_expected));
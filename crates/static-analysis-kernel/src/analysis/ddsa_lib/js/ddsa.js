@@ -7,7 +7,14 @@ import { MethodFlow } from "ext:ddsa_lib/flow/java";
 import { SEALED_EMPTY_ARRAY } from "ext:ddsa_lib/utility";
 import { TreeSitterFieldChildNode } from "ext:ddsa_lib/ts_node";
 
-const { op_digraph_adjacency_list_to_dot, op_ts_node_named_children, op_ts_node_parent } = Deno.core.ops;
+const {
+    op_current_rule_id,
+    op_digraph_adjacency_list_to_dot,
+    op_ts_node_by_path,
+    op_ts_node_named_children,
+    op_ts_node_parent,
+    op_ts_query_matches,
+} = Deno.core.ops;
 
 /**
  * The main entrypoint to the ddsa JavaScript runtime's API.
@@ -42,6 +49,27 @@ export class DDSA {
         return children;
     }
 
+    /**
+     * Fetches and returns the descendant of the provided node at the given `/`-delimited path of node types,
+     * where each step resolves to the first named child with that type.
+     * If any step of the path doesn't exist, `undefined` will be returned.
+     * @param {TreeSitterNode | TreeSitterFieldChildNode} node
+     * @param {string} path
+     * @returns {TreeSitterNode | undefined}
+     *
+     * @example
+     * ```javascript
+     * ddsa.getNodeByPath(node, "function_declaration/formal_parameters/identifier");
+     * ```
+     */
+    getNodeByPath(node, path) {
+        const descendant = op_ts_node_by_path(node.id, path);
+        if (descendant === null) {
+            return undefined;
+        }
+        return descendant;
+    }
+
     /**
      * Fetches and returns the provided node's parent in the tree-sitter tree.
      * If the node is the root node of the tree, `undefined` will be returned.
@@ -56,6 +84,34 @@ export class DDSA {
         return globalThis.__RUST_BRIDGE__ts_node.get(parentId);
     }
 
+    /**
+     * Runs a tree-sitter query against the entire tree of the file being analyzed, returning one object per match.
+     * Captures with a `+` or `*` quantifier map to an array of nodes.
+     *
+     * This allows a rule to run sub-queries that depend on the result of the rule's own query (for example,
+     * finding all references to a variable whose name was captured by the initial query).
+     * @param {string} query A tree-sitter query.
+     * @returns {Array<{captures: Map<string, TreeSitterNode | Array<TreeSitterNode>>}>}
+     *
+     * @throws Throws if the query is invalid.
+     */
+    queryMatches(query) {
+        const matches = op_ts_query_matches(query);
+        if (matches === null) {
+            throw new Error(`invalid tree-sitter query: ${query}`);
+        }
+        return matches;
+    }
+
+    /**
+     * Returns the id of the rule that is currently executing (e.g. `"javascript-best-practices/no-eval"`).
+     * This allows utility functions shared between rules to branch on the rule that called them.
+     * @returns {string}
+     */
+    getRuleId() {
+        return op_current_rule_id();
+    }
+
     /**
      * Returns a backwards flow analysis: a list of `TaintFlow` containing sources of the provided `sinkNode`.
      * @param {TreeSitterNode} sinkNode
@@ -20,9 +20,12 @@ mod tests {
         let expected = &[
             // Methods
             "getChildren",
+            "getNodeByPath",
             "getParent",
+            "getRuleId",
             "getTaintSinks",
             "getTaintSources",
+            "queryMatches",
         ];
         assert!(js_instance_eq(CLASS_NAME, expected));
         let expected = &[];
@@ -141,6 +144,99 @@ function visit(captures) {
         assert_eq!(res.console_lines[0], expected_output);
     }
 
+    /// `op_ts_node_by_path` descends through the first named child of each node type in the path,
+    /// returning `undefined` if any step doesn't exist.
+    #[test]
+    fn op_ts_node_by_path() {
+        use crate::model::common::Language::JavaScript;
+        let mut rt = cfg_test_v8().new_runtime();
+        let text = "function echo(a, b) { /* code */ }";
+        let ts_query = r#"
+(program) @root
+"#;
+        let get_by_path = r#"
+function visit(captures) {
+    const root = captures.get("root");
+    const param = ddsa.getNodeByPath(root, "function_declaration/formal_parameters/identifier");
+    const missing = ddsa.getNodeByPath(root, "function_declaration/arguments/identifier");
+    console.log(param.cstType, param.text, missing);
+}
+"#;
+        let res =
+            shorthand_execute_rule(&mut rt, JavaScript, ts_query, get_by_path, text, None).unwrap();
+        assert_eq!(res.console_lines[0], "identifier a undefined");
+        // Only the captured node and the final node of the path should have been serialized.
+        assert_eq!(rt.bridge_ts_node().borrow().len(), 2);
+    }
+
+    /// `op_ts_query_matches` runs a nested query against the entire tree, returning the captures of each match.
+    #[test]
+    fn op_ts_query_matches() {
+        use crate::model::common::Language::JavaScript;
+        let mut rt = cfg_test_v8().new_runtime();
+        let text = "\
+const secret = getSecret();
+function leak() {
+    log(secret, 1);
+    if (verbose) {
+        log(secret, 2);
+    }
+    log(other, 3);
+}
+";
+        let ts_query = r#"
+(variable_declarator name: (identifier) @name value: (call_expression))
+"#;
+        // Use the name captured by the rule's query to find all references to the variable.
+        let rule_code = r#"
+function visit(captures) {
+    const name = captures.get("name").text;
+    const matches = ddsa.queryMatches(`
+(call_expression
+  function: (identifier) @fn
+  arguments: (arguments (identifier) @arg (number) @num)
+  (#eq? @fn "log")
+)`);
+    for (const match of matches) {
+        const arg = match.captures.get("arg");
+        if (arg.text === name) {
+            console.log(arg.cstType, arg.start.line, match.captures.get("num").text);
+        }
+    }
+}
+"#;
+        let res =
+            shorthand_execute_rule(&mut rt, JavaScript, ts_query, rule_code, text, None).unwrap();
+        assert_eq!(res.console_lines, vec!["identifier 3 1", "identifier 5 2"]);
+    }
+
+    /// Captures with a quantifier are returned as an array, and invalid queries throw.
+    #[test]
+    fn op_ts_query_matches_multi_and_invalid() {
+        use crate::model::common::Language::JavaScript;
+        let mut rt = cfg_test_v8().new_runtime();
+        let text = "function echo(a, b, c) {}";
+        let ts_query = "(program) @root";
+        let rule_code = r#"
+function visit(captures) {
+    const matches = ddsa.queryMatches("(formal_parameters (identifier)+ @params)");
+    const params = matches[0].captures.get("params");
+    console.log(matches.length, params.map((p) => p.text).join(","));
+    try {
+        ddsa.queryMatches("(formal_parameters");
+    } catch (e) {
+        console.log(e.message);
+    }
+}
+"#;
+        let res =
+            shorthand_execute_rule(&mut rt, JavaScript, ts_query, rule_code, text, None).unwrap();
+        assert_eq!(
+            res.console_lines,
+            vec!["1 a,b,c", "invalid tree-sitter query: (formal_parameters"]
+        );
+    }
+
     /// `op_ts_node_parent` only serializes the immediate parent, not the entire ancestor chain.
     /// (We do this test because we know that in order to get a node's parent, in Rust, we are caching the
     /// entire root-to-node path, and we want to ensure we aren't pushing it all to JavaScript at once).
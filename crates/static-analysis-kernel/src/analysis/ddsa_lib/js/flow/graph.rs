@@ -5,6 +5,7 @@
 use crate::analysis::ddsa_lib::bridge::TsNodeBridge;
 use crate::analysis::ddsa_lib::common::{swallow_v8_error, v8_uint};
 use crate::analysis::ddsa_lib::test_utils::TsTree;
+use crate::analysis::tree_sitter::NodePositionExt;
 use deno_core::v8;
 use graphviz_rust::dot_structures;
 use graphviz_rust::dot_structures::{Attribute, Stmt};
@@ -615,10 +616,11 @@ impl From<LocatedEdge<'_>> for dot_structures::Edge {
 impl<'a> LocatedNode<'a> {
     /// Constructs a new `LocatedNode` from a tree-sitter node.
     pub fn new_cst(node: tree_sitter::Node, text: &'a str) -> LocatedNode<'a> {
+        let start = node.start_position_1based();
         Self::Cst {
             text,
-            line: node.start_position().row + 1,
-            col: node.start_position().column + 1,
+            line: start.line as usize,
+            col: start.col as usize,
             cst_kind: node.kind(),
         }
     }
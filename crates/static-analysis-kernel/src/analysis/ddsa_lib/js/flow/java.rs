@@ -96,7 +96,7 @@ mod tests {
     use crate::analysis::ddsa_lib::test_utils::{cfg_test_v8, try_execute, TsTree};
     use crate::analysis::ddsa_lib::v8_ds::V8Converter;
     use crate::analysis::ddsa_lib::{js, JsRuntime};
-    use crate::analysis::tree_sitter::get_tree;
+    use crate::analysis::tree_sitter::{get_tree, NodePositionExt};
     use crate::model::analysis::TreeSitterNode;
     use crate::model::common::Language;
     use crate::model::rule::{RuleCategory, RuleSeverity};
@@ -414,10 +414,11 @@ serialized;
     fn violation_taint_flow_regions() {
         let v_converter = ViolationConverter::new();
         fn position_eq(region: js::CodeRegion<Instance>, node: tree_sitter::Node) -> bool {
-            region.start_line == (node.start_position().row as u32) + 1
-                && region.start_col == (node.start_position().column as u32) + 1
-                && region.end_line == (node.end_position().row as u32) + 1
-                && region.end_col == (node.end_position().column as u32) + 1
+            let (start, end) = (node.start_position_1based(), node.end_position_1based());
+            region.start_line == start.line
+                && region.start_col == start.col
+                && region.end_line == end.line
+                && region.end_col == end.col
         }
 
         // language=java
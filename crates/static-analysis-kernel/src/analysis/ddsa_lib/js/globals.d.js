@@ -6,6 +6,7 @@ import {DDSA_Console} from "ext:ddsa_lib/utility";
 import {QueryMatch} from "ext:ddsa_lib/query_match";
 import {RootContext} from "ext:ddsa_lib/context_root";
 import {TreeSitterNode} from "ext:ddsa_lib/ts_node";
+import {TreeSitterQuery} from "ext:ddsa_lib/ts_query";
 import {Violation} from "ext:ddsa_lib/violation";
 
 /**
@@ -53,3 +54,18 @@ import {Violation} from "ext:ddsa_lib/violation";
  * @type {DDSA}
  * @global
  */
+
+/**
+ * A tree-sitter query that is compiled once and can be run against any node.
+ * @name TreeSitterQuery
+ * @type {typeof TreeSitterQuery}
+ * @global
+ */
+
+/**
+ * The byte offset at which each line of the file being analyzed starts (the first entry is always `0`).
+ * Each rule execution receives its own copy, so mutating it doesn't affect other rules.
+ * @name DD_LINE_OFFSETS
+ * @type {Uint32Array}
+ * @global
+ */
@@ -0,0 +1,17 @@
+// Unless explicitly stated otherwise all files in this repository are licensed under the Apache License, Version 2.0.
+// This product includes software developed at Datadog (https://www.datadoghq.com/).
+// Copyright 2024 Datadog, Inc.
+
+const { op_report_metric } = Deno.core.ops;
+
+/**
+ * Reports a metric for the file currently being scanned. Metrics are buffered by the Rust runtime
+ * and flushed once the scan of the file has completed.
+ * @param {string} name The name of the metric (e.g. `"rule.latency.ms"`).
+ * @param {number} value
+ * @param {Object<string, string>} [tags] An optional set of key-value tags to attach to the metric.
+ * @returns {void}
+ */
+export function reportMetric(name, value, tags) {
+    op_report_metric(name, value, tags);
+}
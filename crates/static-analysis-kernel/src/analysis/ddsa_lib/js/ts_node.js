@@ -2,7 +2,14 @@
 // This product includes software developed at Datadog (https://www.datadoghq.com/).
 // Copyright 2024 Datadog, Inc.
 
-const { op_ts_node_text } = Deno.core.ops;
+const {
+    op_ts_node_end_byte,
+    op_ts_node_sibling_count,
+    op_ts_node_source_range,
+    op_ts_node_start_byte,
+    op_ts_node_text,
+    op_ts_node_utf8_text_bytes,
+} = Deno.core.ops;
 
 /**
  * A non-zero integer assigned by the Rust static-analysis-kernel.
@@ -23,6 +30,18 @@ const { op_ts_node_text } = Deno.core.ops;
  * @property {number} col
  */
 
+/**
+ * The full range of a node within a source text. Byte offsets are 0-based (`endByte` is exclusive),
+ * and lines and columns are 1-based.
+ * @typedef {Object} SourceRange
+ * @property {number} startByte
+ * @property {number} endByte
+ * @property {number} startLine
+ * @property {number} startCol
+ * @property {number} endLine
+ * @property {number} endCol
+ */
+
 /**
  * An object representing a node within a `tree-sitter Tree, as well as functions to access metadata about
  * both itself and its relationship to its context.
@@ -108,6 +127,63 @@ export class TreeSitterNode {
         return this.__js_cachedText;
     }
 
+    /**
+     * A getter to return the UTF-8 bytes of the source text that this `TreeSitterNode` spans.
+     * @returns {Uint8Array | undefined}
+     *
+     * @remarks
+     * Unlike {@link TreeSitterNode.text}, this value is not cached: each access makes a call to Rust.
+     * A file that is not valid UTF-8 is transcoded to UTF-8 before the analysis, so these bytes can
+     * differ from the bytes of the file on disk.
+     */
+    get textBytes() {
+        const opResult = op_ts_node_utf8_text_bytes(this.id);
+        if (opResult === null) {
+            return undefined;
+        }
+        return opResult;
+    }
+
+    /**
+     * A getter to return the byte offset within the source text at which this node starts.
+     * @returns {number}
+     */
+    get startByte() {
+        return op_ts_node_start_byte(this.id);
+    }
+
+    /**
+     * A getter to return the byte offset within the source text at which this node ends (exclusive).
+     * @returns {number}
+     */
+    get endByte() {
+        return op_ts_node_end_byte(this.id);
+    }
+
+    /**
+     * A getter to return the full {@link SourceRange} of this node, retrieved with a single call to Rust.
+     * @returns {SourceRange | undefined}
+     */
+    get sourceRange() {
+        const opResult = op_ts_node_source_range(this.id);
+        if (opResult === null) {
+            return undefined;
+        }
+        return opResult;
+    }
+
+    /**
+     * A getter to return the number of named children of this node's parent, including this node.
+     * This is `0` for the root node.
+     * @returns {number}
+     *
+     * @remarks
+     * Unlike retrieving the parent's children, this doesn't require a {@link TreeSitterNode} to be created for each sibling.
+     */
+    get siblingCount() {
+        return op_ts_node_sibling_count(this.id);
+    }
+
     /**
      * A getter to return the start {@link Position} of this node.
      * Note that this getter returns a cached object -- the caller should not mutate it.
@@ -5,6 +5,7 @@
 use crate::analysis::ddsa_lib::common::{
     load_function, swallow_v8_error, v8_uint, Class, DDSAJsRuntimeError, Instance, NodeId,
 };
+use crate::analysis::tree_sitter::NodePositionExt;
 use deno_core::v8;
 use deno_core::v8::HandleScope;
 use std::marker::PhantomData;
@@ -27,23 +28,14 @@ impl<T> TreeSitterNode<T> {
     /// Converts the provided [`tree_sitter::Node`] into a `TreeSitterNode`, assigning the provided id.
     pub fn from_ts_node(id: NodeId, node: tree_sitter::Node) -> Self {
         // NOTE: We normalize the 0-based `tree_sitter::Point` to be 1-based.
-        fn normalize_ts_point_num(num: usize) -> u32 {
-            num as u32 + 1
-        }
-        let tree_sitter::Point {
-            row: start_line,
-            column: start_col,
-        } = node.start_position();
-        let tree_sitter::Point {
-            row: end_line,
-            column: end_col,
-        } = node.end_position();
+        let start = node.start_position_1based();
+        let end = node.end_position_1based();
         Self {
             id,
-            start_line: normalize_ts_point_num(start_line),
-            start_col: normalize_ts_point_num(start_col),
-            end_line: normalize_ts_point_num(end_line),
-            end_col: normalize_ts_point_num(end_col),
+            start_line: start.line,
+            start_col: start.col,
+            end_line: end.line,
+            end_col: end.col,
             node_type_id: node.kind_id(),
             _pd: PhantomData,
         }
@@ -100,7 +92,7 @@ mod tests {
     use crate::analysis::ddsa_lib::js::{TreeSitterNode, TreeSitterNodeFn};
     use crate::analysis::ddsa_lib::test_utils::{
         attach_as_global, cfg_test_v8, js_class_eq, js_instance_eq, make_stub_root_context,
-        try_execute,
+        shorthand_execute_rule, try_execute,
     };
     use crate::analysis::tree_sitter::get_tree_sitter_language;
     use crate::model::common::Language;
@@ -123,6 +115,11 @@ mod tests {
             "__js_cachedText",
             // Methods
             "text",
+            "textBytes",
+            "startByte",
+            "endByte",
+            "sourceRange",
+            "siblingCount",
             "cstType",
             "start",
             "end",
@@ -323,4 +320,115 @@ assert(child._fieldId === 30, "_fieldId value incorrect");
         let res = try_execute(scope, code).unwrap();
         assert!(res.is_uint32() && res.uint32_value(scope).unwrap() == 123);
     }
+
+    /// Tests that `textBytes` returns the raw bytes of the node's text, which can differ in length
+    /// from the UTF-16 `text` string when the source contains multibyte characters.
+    #[test]
+    fn text_bytes_getter() {
+        let mut rt = cfg_test_v8().new_runtime();
+        let text = "const s = 'café';";
+        let ts_query = "(string) @str";
+        let code = r#"
+function visit(captures) {
+    const node = captures.get("str");
+    const bytes = node.textBytes;
+    console.log(node.text.length, bytes.length, Array.from(bytes.slice(4, 6)));
+}
+"#;
+        let res = shorthand_execute_rule(&mut rt, Language::JavaScript, ts_query, code, text, None)
+            .unwrap();
+        // "é" is encoded as the two bytes 0xC3 0xA9.
+        assert_eq!(res.console_lines[0], "6 7 [195,169]");
+    }
+
+    /// Tests that `textBytes` returns the UTF-8 encoding of a file that was transcoded from Latin-1,
+    /// rather than the original bytes of the file.
+    #[test]
+    fn text_bytes_getter_latin1() {
+        let mut rt = cfg_test_v8().new_runtime();
+        // Latin-1 maps each byte to the code point of the same value.
+        let text = b"const s = 'caf\xe9';"
+            .iter()
+            .map(|&b| char::from(b))
+            .collect::<String>();
+        let ts_query = "(string) @str";
+        let code = r#"
+function visit(captures) {
+    const node = captures.get("str");
+    console.log(Array.from(node.textBytes.slice(4, 6)));
+}
+"#;
+        let res =
+            shorthand_execute_rule(&mut rt, Language::JavaScript, ts_query, code, &text, None)
+                .unwrap();
+        // The Latin-1 byte 0xE9 is returned as its UTF-8 encoding 0xC3 0xA9.
+        assert_eq!(res.console_lines[0], "[195,169]");
+    }
+
+    /// Tests that `startByte` and `endByte` return the node's byte range within the source text.
+    #[test]
+    fn byte_range_getters() {
+        let mut rt = cfg_test_v8().new_runtime();
+        let text = "const abc = 'hello world';";
+        let ts_query = "(string) @str";
+        let code = r#"
+function visit(captures) {
+    const node = captures.get("str");
+    console.log(node.startByte, node.endByte, node.endByte - node.startByte === node.text.length);
+}
+"#;
+        let res = shorthand_execute_rule(&mut rt, Language::JavaScript, ts_query, code, text, None)
+            .unwrap();
+        assert_eq!(res.console_lines[0], "12 25 true");
+    }
+
+    /// Tests that `sourceRange` returns the node's byte offsets and 1-based positions in a single object.
+    #[test]
+    fn source_range_getter() {
+        let mut rt = cfg_test_v8().new_runtime();
+        let text = "\
+const abc = 123;
+const def = `hello
+world`;
+";
+        let ts_query = "(template_string) @str";
+        let code = r#"
+function visit(captures) {
+    const node = captures.get("str");
+    const r = node.sourceRange;
+    console.log(r.startByte, r.endByte, r.startLine, r.startCol, r.endLine, r.endCol);
+    console.log(r.startByte === node.startByte, r.endLine === node.end.line, r.endCol === node.end.col);
+}
+"#;
+        let res = shorthand_execute_rule(&mut rt, Language::JavaScript, ts_query, code, text, None)
+            .unwrap();
+        assert_eq!(res.console_lines[0], "29 42 2 13 3 7");
+        assert_eq!(res.console_lines[1], "true true true");
+    }
+
+    /// Tests that `siblingCount` returns the number of named children of the node's parent.
+    #[test]
+    fn sibling_count_getter() {
+        let mut rt = cfg_test_v8().new_runtime();
+        let text = "\
+function f() {
+    const a = 1;
+    const b = 2;
+    // comment
+    return a + b;
+}
+";
+        let ts_query = "(statement_block (return_statement) @ret) @block";
+        let code = r#"
+function visit(captures) {
+    const ret = captures.get("ret");
+    const block = captures.get("block");
+    const root = ddsa.getParent(ddsa.getParent(block));
+    console.log(ret.siblingCount, block.siblingCount, root.cstType, root.siblingCount);
+}
+"#;
+        let res = shorthand_execute_rule(&mut rt, Language::JavaScript, ts_query, code, text, None)
+            .unwrap();
+        assert_eq!(res.console_lines[0], "4 3 program 0");
+    }
 }
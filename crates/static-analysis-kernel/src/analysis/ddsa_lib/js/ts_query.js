@@ -0,0 +1,61 @@
+// Unless explicitly stated otherwise all files in this repository are licensed under the Apache License, Version 2.0.
+// This product includes software developed at Datadog (https://www.datadoghq.com/).
+// Copyright 2024 Datadog, Inc.
+
+const {
+    op_compile_ts_query,
+    op_ts_query_node_matches,
+} = Deno.core.ops;
+
+/**
+ * A tree-sitter query that is compiled once, and can then be run against any node of the file being analyzed.
+ *
+ * @example
+ * ```javascript
+ * const params = new TreeSitterQuery("(formal_parameters (identifier) @param)");
+ *
+ * function visit(captures) {
+ *     for (const match of params.matches(captures.get("fn"))) {
+ *         console.log(match.captures.get("param").text);
+ *     }
+ * }
+ * ```
+ */
+export class TreeSitterQuery {
+    /**
+     * @param {string} query A tree-sitter query.
+     *
+     * @throws Throws if the query is invalid.
+     */
+    constructor(query) {
+        if (!op_compile_ts_query(query)) {
+            throw new Error(`invalid tree-sitter query: ${query}`);
+        }
+        /**
+         * The text of the tree-sitter query.
+         * @type {string}
+         * @readonly
+         */
+        this.query = query;
+    }
+
+    /**
+     * Runs this query against the provided node and its descendants, returning one object per match.
+     * Captures with a `+` or `*` quantifier map to an array of nodes.
+     * @param {TreeSitterNode | TreeSitterFieldChildNode} node
+     * @returns {Array<{captures: Map<string, TreeSitterNode | Array<TreeSitterNode>>}>}
+     *
+     * @remarks
+     * The compiled query is cached by the Rust static-analysis-kernel, so creating a `TreeSitterQuery` with
+     * the same text in a later execution doesn't recompile it.
+     *
+     * @throws Throws if the query is invalid for the language of the file being analyzed.
+     */
+    matches(node) {
+        const matches = op_ts_query_node_matches(this.query, node.id);
+        if (matches === null) {
+            throw new Error(`invalid tree-sitter query: ${this.query}`);
+        }
+        return matches;
+    }
+}
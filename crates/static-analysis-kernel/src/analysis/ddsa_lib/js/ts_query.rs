@@ -0,0 +1,137 @@
+// Unless explicitly stated otherwise all files in this repository are licensed under the Apache License, Version 2.0.
+// This product includes software developed at Datadog (https://www.datadoghq.com/).
+// Copyright 2024 Datadog, Inc.
+
+use crate::analysis::ddsa_lib::common::Class;
+use std::marker::PhantomData;
+
+/// The JavaScript `TreeSitterQuery` class. (There is no Rust business logic here: queries are compiled
+/// and run via ops, and the compiled queries are cached by the [`ContextBridge`](crate::analysis::ddsa_lib::bridge::ContextBridge)).
+#[derive(Debug)]
+pub struct TreeSitterQueryFn<T>(PhantomData<T>);
+
+impl TreeSitterQueryFn<Class> {
+    pub const CLASS_NAME: &'static str = "TreeSitterQuery";
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::analysis::ddsa_lib::js::TreeSitterQueryFn;
+    use crate::analysis::ddsa_lib::test_utils::{cfg_test_v8, js_class_eq, shorthand_execute_rule};
+    use crate::model::common::Language;
+    use std::sync::Arc;
+
+    #[test]
+    fn js_properties_canary() {
+        // The constructor compiles the query, which requires a tree, so (unlike `js_instance_eq`) the
+        // instance is inspected from within a rule execution.
+        let mut rt = cfg_test_v8().new_runtime();
+        let code = r#"
+function visit(captures) {
+    const instance = new TreeSitterQuery("(identifier) @id");
+    const variables = Object.getOwnPropertyNames(instance).sort();
+    const methods = Object.getOwnPropertyNames(TreeSitterQuery.prototype)
+        .filter((p) => p !== "constructor")
+        .sort();
+    console.log([...variables, ...methods].join(","));
+}
+"#;
+        let res = shorthand_execute_rule(
+            &mut rt,
+            Language::JavaScript,
+            "(program) @root",
+            code,
+            "const abc = 1;",
+            None,
+        )
+        .unwrap();
+        // (Short items are otherwise packed onto one line, which would move the comments).
+        #[rustfmt::skip]
+        let expected = &[
+            // Variables
+            "query",
+            // Methods
+            "matches",
+        ];
+        assert_eq!(res.console_lines, vec![expected.join(",")]);
+        let expected = &[];
+        assert!(js_class_eq(TreeSitterQueryFn::CLASS_NAME, expected));
+    }
+
+    /// A query only matches within the subtree of the node it's run against, and invalid queries throw.
+    #[test]
+    fn query_matches_node_subtree() {
+        let mut rt = cfg_test_v8().new_runtime();
+        let text = "\
+function a(x, y) {}
+function b(z) {}
+";
+        let ts_query = "(function_declaration name: (identifier) @name) @fn";
+        let code = r#"
+const params = new TreeSitterQuery("(formal_parameters (identifier) @param)");
+
+function visit(captures) {
+    const found = params.matches(captures.get("fn")).map((m) => m.captures.get("param").text);
+    console.log(captures.get("name").text, found.join(","));
+}
+
+try {
+    new TreeSitterQuery("(formal_parameters");
+} catch (e) {
+    console.log(e.message);
+}
+"#;
+        let res = shorthand_execute_rule(&mut rt, Language::JavaScript, ts_query, code, text, None)
+            .unwrap();
+        assert_eq!(
+            res.console_lines,
+            vec![
+                "invalid tree-sitter query: (formal_parameters",
+                "a x,y",
+                "b z"
+            ]
+        );
+    }
+
+    /// A query compiled in one rule execution is reused by later executions.
+    #[test]
+    fn query_reused_across_executions() {
+        let mut rt = cfg_test_v8().new_runtime();
+        let query = "(identifier) @id";
+        let ts_query = "(program) @root";
+        let code = format!(
+            r#"
+const ids = new TreeSitterQuery("{query}");
+
+function visit(captures) {{
+    console.log(ids.matches(captures.get("root")).length);
+}}
+"#
+        );
+
+        let res = shorthand_execute_rule(
+            &mut rt,
+            Language::JavaScript,
+            ts_query,
+            &code,
+            "const abc = 1;",
+            None,
+        )
+        .unwrap();
+        assert_eq!(res.console_lines, vec!["1"]);
+        let first = rt.bridge_context().borrow_mut().ts_query(query).unwrap();
+
+        let res = shorthand_execute_rule(
+            &mut rt,
+            Language::JavaScript,
+            ts_query,
+            &code,
+            "const def = ghi;",
+            None,
+        )
+        .unwrap();
+        assert_eq!(res.console_lines, vec!["2"]);
+        let second = rt.bridge_context().borrow_mut().ts_query(query).unwrap();
+        assert!(Arc::ptr_eq(&first, &second));
+    }
+}
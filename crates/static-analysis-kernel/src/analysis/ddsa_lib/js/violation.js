@@ -6,6 +6,24 @@ import { CodeRegion } from "ext:ddsa_lib/region";
 import { TaintFlow } from "ext:ddsa_lib/flow/graph";
 import { TreeSitterNode } from "ext:ddsa_lib/ts_node";
 
+/**
+ * A single step of a code flow.
+ * @typedef {Object} CodeFlowLocation
+ * @property {string} file The path of the file containing this step.
+ * @property {number} line
+ * @property {number} col
+ * @property {string} message A message describing this step.
+ */
+
+/**
+ * A secondary location referenced by a violation's message (for example, "see also ...").
+ * @typedef {Object} RelatedLocation
+ * @property {string} message A message describing how this location relates to the violation.
+ * @property {string} file The path of the file containing this location.
+ * @property {number} line
+ * @property {number} col
+ */
+
 /**
  * A static analysis violation to be reported to the Rust static analysis kernel.
  */
@@ -47,6 +65,18 @@ export class Violation {
          * @type {Array<CodeRegion> | undefined}
          */
         this.taintFlowRegions = undefined;
+        /**
+         * An optional list of code flows associated with this Violation. Each code flow is an ordered list
+         * of code locations that together constitute the violation (for example, the steps from a tainted
+         * source to a sink).
+         * @type {Array<Array<CodeFlowLocation>> | undefined}
+         */
+        this.codeFlows = undefined;
+        /**
+         * An optional list of secondary locations referenced by this Violation's message (for example, "see also ...").
+         * @type {Array<RelatedLocation> | undefined}
+         */
+        this.relatedLocations = undefined;
 
         if (locationArgs.length > 0) {
             const location = inferRegionVariadic(locationArgs);
@@ -72,6 +102,32 @@ export class Violation {
         return this;
     }
 
+    /**
+     * @param {Array<CodeFlowLocation>} locations The steps of the flow, ordered from start to finish.
+     * @returns {Violation}
+     * Adds a code flow to this violation and returns `this`.
+     */
+    addCodeFlow(locations) {
+        if (this.codeFlows === undefined) {
+            this.codeFlows = [];
+        }
+        this.codeFlows.push(locations);
+        return this;
+    }
+
+    /**
+     * @param {RelatedLocation} relatedLocation
+     * @returns {Violation}
+     * Adds a related location to this violation and returns `this`.
+     */
+    addRelatedLocation(relatedLocation) {
+        if (this.relatedLocations === undefined) {
+            this.relatedLocations = [];
+        }
+        this.relatedLocations.push(relatedLocation);
+        return this;
+    }
+
     /**
      * Creates a new `Violation`.
      * @param {string} message
@@ -22,6 +22,8 @@ pub struct Violation<T> {
     pub fixes: Option<Vec<Fix<T>>>,
     pub base_region: CodeRegion<T>,
     pub taint_flow_regions: Option<Vec<CodeRegion<T>>>,
+    pub code_flows: Option<Vec<violation::CodeFlow>>,
+    pub related_locations: Option<Vec<violation::RelatedLocation>>,
     /// (See documentation on [`Instance`]).
     pub _pd: PhantomData<T>,
 }
@@ -54,6 +56,8 @@ impl Violation<Instance> {
             category,
             fixes,
             taint_flow,
+            code_flows: self.code_flows.unwrap_or_default(),
+            related_locations: self.related_locations.unwrap_or_default(),
         }
     }
 }
@@ -111,17 +115,88 @@ impl V8Converter for ViolationConverter {
                     .collect::<Result<Vec<_>, _>>()
             })
             .transpose()?;
+        let code_flows =
+            get_optional_field::<v8::Array>(v8_obj, "codeFlows", scope, "array | undefined")?;
+        let code_flows = code_flows
+            .map(|array| {
+                iter_v8_array(array, scope)
+                    .map(|value| {
+                        let flow = v8_type_from::<v8::Array>(value, "array")?;
+                        iter_v8_array(flow, scope)
+                            .map(|value| code_flow_location_from(scope, value))
+                            .collect::<Result<violation::CodeFlow, _>>()
+                    })
+                    .collect::<Result<Vec<_>, _>>()
+            })
+            .transpose()?;
+        let related_locations = get_optional_field::<v8::Array>(
+            v8_obj,
+            "relatedLocations",
+            scope,
+            "array | undefined",
+        )?;
+        let related_locations = related_locations
+            .map(|array| {
+                iter_v8_array(array, scope)
+                    .map(|value| related_location_from(scope, value))
+                    .collect::<Result<Vec<_>, _>>()
+            })
+            .transpose()?;
 
         Ok(Violation {
             message,
             fixes,
             base_region,
             taint_flow_regions,
+            code_flows,
+            related_locations,
             _pd: PhantomData,
         })
     }
 }
 
+/// Converts a v8 value with the shape `{ file: string, line: number, col: number, message: string }`
+/// to a [`violation::CodeFlowLocation`].
+fn code_flow_location_from<'s>(
+    scope: &mut HandleScope<'s>,
+    value: v8::Local<'s, v8::Value>,
+) -> Result<violation::CodeFlowLocation, DDSAJsRuntimeError> {
+    let v8_obj = v8_type_from::<v8::Object>(value, "an object")?;
+    let file =
+        get_field::<v8::String>(v8_obj, "file", scope, "string")?.to_rust_string_lossy(scope);
+    let line = get_field::<v8::Integer>(v8_obj, "line", scope, "number")?.value() as u32;
+    let col = get_field::<v8::Integer>(v8_obj, "col", scope, "number")?.value() as u32;
+    let message =
+        get_field::<v8::String>(v8_obj, "message", scope, "string")?.to_rust_string_lossy(scope);
+    Ok(violation::CodeFlowLocation {
+        file,
+        line,
+        col,
+        message,
+    })
+}
+
+/// Converts a v8 value with the shape `{ message: string, file: string, line: number, col: number }`
+/// to a [`violation::RelatedLocation`].
+fn related_location_from<'s>(
+    scope: &mut HandleScope<'s>,
+    value: v8::Local<'s, v8::Value>,
+) -> Result<violation::RelatedLocation, DDSAJsRuntimeError> {
+    let v8_obj = v8_type_from::<v8::Object>(value, "an object")?;
+    let message =
+        get_field::<v8::String>(v8_obj, "message", scope, "string")?.to_rust_string_lossy(scope);
+    let file =
+        get_field::<v8::String>(v8_obj, "file", scope, "string")?.to_rust_string_lossy(scope);
+    let line = get_field::<v8::Integer>(v8_obj, "line", scope, "number")?.value() as u32;
+    let col = get_field::<v8::Integer>(v8_obj, "col", scope, "number")?.value() as u32;
+    Ok(violation::RelatedLocation {
+        message,
+        file,
+        line,
+        col,
+    })
+}
+
 #[cfg(test)]
 mod tests {
     use crate::analysis::ddsa_lib::common::Instance;
@@ -140,8 +215,12 @@ mod tests {
             "message",
             "baseRegion",
             "taintFlowRegions",
+            "codeFlows",
+            "relatedLocations",
             // Methods
             "addFix",
+            "addCodeFlow",
+            "addRelatedLocation",
         ];
         assert!(js_instance_eq(Violation::CLASS_NAME, instance_exp));
         let class_expected = &["new"];
@@ -173,6 +252,8 @@ mod tests {
             fixes: None,
             base_region: region0,
             taint_flow_regions: None,
+            code_flows: None,
+            related_locations: None,
             _pd: PhantomData,
         };
 
@@ -203,6 +284,8 @@ Violation.new("abc", tsNode);
             fixes: None,
             base_region: region0,
             taint_flow_regions: Some(vec![region0, region1]),
+            code_flows: None,
+            related_locations: None,
             _pd: PhantomData,
         };
         let flow_variants = &[r#"
@@ -0,0 +1,141 @@
+// Unless explicitly stated otherwise all files in this repository are licensed under the Apache License, Version 2.0.
+// This product includes software developed at Datadog (https://www.datadoghq.com/).
+// Copyright 2024 Datadog, Inc.
+
+use std::collections::BTreeMap;
+
+/// A metric reported by a JavaScript rule via `reportMetric`.
+///
+/// The shape intentionally mirrors an OpenTelemetry gauge data point: a name, a numeric value,
+/// and a set of string attributes.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Metric {
+    pub name: String,
+    pub value: f64,
+    pub tags: BTreeMap<String, String>,
+}
+
+/// A destination for the metrics reported while scanning a single file.
+pub trait MetricsSink {
+    /// Consumes all metrics reported during the scan of `file_name`.
+    fn flush(&mut self, file_name: &str, metrics: Vec<Metric>);
+}
+
+/// A [`MetricsSink`] that discards all metrics.
+#[derive(Debug, Default, Copy, Clone)]
+pub struct NoopMetricsSink;
+
+impl MetricsSink for NoopMetricsSink {
+    fn flush(&mut self, _file_name: &str, _metrics: Vec<Metric>) {}
+}
+
+/// A [`MetricsSink`] that prints each metric to stderr. This is intended for debugging.
+#[derive(Debug, Default, Copy, Clone)]
+pub struct PrintMetricsSink;
+
+impl MetricsSink for PrintMetricsSink {
+    fn flush(&mut self, file_name: &str, metrics: Vec<Metric>) {
+        for metric in metrics {
+            let tags = metric
+                .tags
+                .iter()
+                .map(|(k, v)| format!("{k}:{v}"))
+                .collect::<Vec<_>>()
+                .join(",");
+            eprintln!(
+                "[metric] {} {}={} [{}]",
+                file_name, metric.name, metric.value, tags
+            );
+        }
+    }
+}
+
+/// A mutable scratch space that buffers the metrics reported by JavaScript code until they are flushed.
+#[derive(Debug, Default)]
+pub(crate) struct MetricsBuffer(Vec<Metric>);
+
+impl MetricsBuffer {
+    /// Creates a new, empty `MetricsBuffer`.
+    pub fn new() -> Self {
+        Self(Vec::new())
+    }
+
+    /// Appends a metric to the buffer.
+    pub fn push(&mut self, metric: Metric) {
+        self.0.push(metric)
+    }
+
+    /// Returns the number of buffered metrics.
+    #[cfg(test)]
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    /// Returns `true` if there are no buffered metrics.
+    #[cfg(test)]
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// Removes all metrics from the `MetricsBuffer`, returning them.
+    pub fn take(&mut self) -> Vec<Metric> {
+        std::mem::take(&mut self.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::analysis::ddsa_lib::metrics::{Metric, MetricsSink};
+    use crate::analysis::ddsa_lib::test_utils::{cfg_test_v8, shorthand_execute_rule};
+    use crate::model::common::Language;
+    use std::cell::RefCell;
+    use std::collections::BTreeMap;
+    use std::rc::Rc;
+
+    #[derive(Default)]
+    struct CollectingSink(Rc<RefCell<Vec<(String, Vec<Metric>)>>>);
+
+    impl MetricsSink for CollectingSink {
+        fn flush(&mut self, file_name: &str, metrics: Vec<Metric>) {
+            self.0.borrow_mut().push((file_name.to_string(), metrics));
+        }
+    }
+
+    /// Metrics are buffered across rule executions and only handed to the sink upon flush.
+    #[test]
+    fn report_metric_buffer_and_flush() {
+        let mut rt = cfg_test_v8().new_runtime();
+        let flushed = Rc::new(RefCell::new(Vec::new()));
+        rt.set_metrics_sink(Box::new(CollectingSink(Rc::clone(&flushed))));
+
+        let text = "const abc = 123;";
+        let ts_query = "(identifier) @cap";
+        let code = r#"
+function visit(captures) {
+    reportMetric("rule.latency.ms", 42, { tag: "value" });
+    reportMetric("rule.count", 1);
+}
+"#;
+        shorthand_execute_rule(&mut rt, Language::JavaScript, ts_query, code, text, None).unwrap();
+        shorthand_execute_rule(&mut rt, Language::JavaScript, ts_query, code, text, None).unwrap();
+        // Nothing should be flushed until explicitly requested.
+        assert!(flushed.borrow().is_empty());
+        assert_eq!(rt.metrics_buffer().borrow().len(), 4);
+
+        rt.flush_metrics("file.js");
+        assert!(rt.metrics_buffer().borrow().is_empty());
+        let flushed = flushed.borrow();
+        assert_eq!(flushed.len(), 1);
+        let (file_name, metrics) = &flushed[0];
+        assert_eq!(file_name, "file.js");
+        assert_eq!(metrics.len(), 4);
+        let expected = Metric {
+            name: "rule.latency.ms".to_string(),
+            value: 42.0,
+            tags: BTreeMap::from([("tag".to_string(), "value".to_string())]),
+        };
+        assert_eq!(metrics[0], expected);
+        assert_eq!(metrics[1].name, "rule.count");
+        assert!(metrics[1].tags.is_empty());
+    }
+}
@@ -3,10 +3,12 @@
 // Copyright 2024 Datadog, Inc.
 
 use crate::analysis::ddsa_lib;
-use crate::analysis::ddsa_lib::common::{v8_uint, NodeId};
-use crate::analysis::ddsa_lib::{bridge, runtime, RawTSNode};
+use crate::analysis::ddsa_lib::common::{v8_interned, v8_string, v8_uint, NodeId};
+use crate::analysis::ddsa_lib::{bridge, metrics, runtime, RawTSNode};
+use crate::analysis::tree_sitter::{NodePositionExt, QueryMatch, TSCaptureContent};
 use deno_core::{op2, v8, OpState};
 use std::cell::RefCell;
+use std::collections::BTreeMap;
 use std::rc::Rc;
 
 #[op2(fast)]
@@ -18,20 +20,39 @@ pub fn op_console_push(state: &mut OpState, #[string] line: &str) {
     console.push(line);
 }
 
+/// Buffers a metric reported by a rule. Buffered metrics are flushed to the runtime's
+/// [`MetricsSink`](crate::analysis::ddsa_lib::metrics::MetricsSink) after each file is scanned.
+#[op2]
+pub fn op_report_metric(
+    state: &mut OpState,
+    #[string] name: String,
+    value: f64,
+    #[serde] tags: Option<BTreeMap<String, String>>,
+) {
+    let buffer = state.borrow::<Rc<RefCell<metrics::MetricsBuffer>>>();
+    let mut buffer = buffer
+        .try_borrow_mut()
+        .expect("metrics buffer should only be accessed via sequential executions");
+    buffer.push(metrics::Metric {
+        name,
+        value,
+        tags: tags.unwrap_or_default(),
+    });
+}
+
 /// Returns a string containing the text used to generate the tree-sitter tree.
 ///
-/// # Panics
-/// Panics if the [`ddsa_lib::RootContext`](crate::analysis::ddsa_lib::RootContext) has not set the tree's text.
+/// If the [`ddsa_lib::RootContext`](crate::analysis::ddsa_lib::RootContext) has no text (for example,
+/// after the context has been cleared), `None` is returned.
 #[op2]
 #[string]
-pub fn op_current_ts_tree_text(state: &OpState) -> String {
+pub fn op_current_ts_tree_text(state: &OpState) -> Option<String> {
     let ctx_bridge = state.borrow::<Rc<RefCell<bridge::ContextBridge>>>();
     ctx_bridge
         .borrow()
         .ddsa_root_context()
         .get_text()
-        .expect("tree text should always be `Some` during rule execution")
-        .to_string()
+        .map(ToString::to_string)
 }
 
 /// Returns a string containing the filename of the file being scanned.
@@ -50,6 +71,158 @@ pub fn op_current_filename(state: &OpState) -> String {
         .to_string()
 }
 
+/// Returns a string containing the id of the rule that is currently executing.
+///
+/// # Panics
+/// Panics if the [`ddsa_lib::RuleContext`](crate::analysis::ddsa_lib::RuleContext) has not set the rule id.
+#[op2]
+#[string]
+pub fn op_current_rule_id(state: &OpState) -> String {
+    let ctx_bridge = state.borrow::<Rc<RefCell<bridge::ContextBridge>>>();
+    ctx_bridge
+        .borrow()
+        .ddsa_rule_context()
+        .get_rule_id()
+        .expect("rule id should always be `Some` during rule execution")
+        .to_string()
+}
+
+/// Runs a tree-sitter query against the tree of the file being scanned, returning an array with
+/// a `{ captures: Map<string, TreeSitterNode | Array<TreeSitterNode>> }` object for each match.
+/// Captures with a `+` or `*` quantifier map to an array of nodes.
+///
+/// Compiled queries are cached by the [`ContextBridge`](bridge::ContextBridge).
+///
+/// The query is bounded by the time remaining in the rule's execution. If that runs out, the matches
+/// found so far are returned, and the execution is then terminated as timed out.
+///
+/// If the query is invalid, `None` is returned.
+///
+/// # Panics
+/// Panics if the [`ddsa_lib::RootContext`](crate::analysis::ddsa_lib::RootContext) has not set the tree or its text.
+#[op2]
+pub fn op_ts_query_matches<'s>(
+    state: &OpState,
+    scope: &mut v8::HandleScope<'s>,
+    #[string] query: &str,
+) -> Option<v8::Local<'s, v8::Array>> {
+    let ctx_bridge = state.borrow::<Rc<RefCell<bridge::ContextBridge>>>();
+    let ts_query = ctx_bridge.borrow_mut().ts_query(query)?;
+    let ctx_bridge = ctx_bridge.borrow();
+    let root_ctx = ctx_bridge.ddsa_root_context();
+    let tree = root_ctx
+        .get_tree()
+        .expect("tree should always be `Some` during rule execution");
+    let tree_text = root_ctx
+        .get_text()
+        .expect("tree text should always be `Some` during rule execution");
+    let mut ts_node_bridge = state
+        .borrow::<Rc<RefCell<bridge::TsNodeBridge>>>()
+        .borrow_mut();
+
+    let timeout = state
+        .borrow::<Rc<RefCell<runtime::ExecutionDeadline>>>()
+        .borrow()
+        .remaining();
+
+    let mut cursor = ts_query.cursor();
+    let query_matches = cursor
+        .matches(tree.root_node(), tree_text, timeout)
+        .filter(|captures| !captures.is_empty())
+        .collect::<Vec<_>>();
+    Some(v8_query_matches(scope, &mut ts_node_bridge, query_matches))
+}
+
+/// Compiles a tree-sitter query for the language of the file being scanned. The compiled query is
+/// cached by the [`ContextBridge`](bridge::ContextBridge), so it's only compiled once per language.
+///
+/// Returns `false` if the query is invalid.
+#[op2]
+pub fn op_compile_ts_query(state: &OpState, #[string] query: &str) -> bool {
+    let ctx_bridge = state.borrow::<Rc<RefCell<bridge::ContextBridge>>>();
+    ctx_bridge.borrow_mut().ts_query(query).is_some()
+}
+
+/// Runs a tree-sitter query against the subtree rooted at the provided node, returning an array with
+/// a `{ captures: Map<string, TreeSitterNode | Array<TreeSitterNode>> }` object for each match.
+///
+/// Like [`op_ts_query_matches`], the query is bounded by the time remaining in the rule's execution.
+///
+/// If the query is invalid, or the node doesn't exist, `None` is returned.
+///
+/// # Panics
+/// Panics if the [`ddsa_lib::RootContext`](crate::analysis::ddsa_lib::RootContext) has not set the tree's text.
+#[op2]
+pub fn op_ts_query_node_matches<'s>(
+    state: &OpState,
+    scope: &mut v8::HandleScope<'s>,
+    #[string] query: &str,
+    #[smi] node_id: u32,
+) -> Option<v8::Local<'s, v8::Array>> {
+    let ctx_bridge = state.borrow::<Rc<RefCell<bridge::ContextBridge>>>();
+    let ts_query = ctx_bridge.borrow_mut().ts_query(query)?;
+    let ctx_bridge = ctx_bridge.borrow();
+    let tree_text = ctx_bridge
+        .ddsa_root_context()
+        .get_text()
+        .expect("tree text should always be `Some` during rule execution");
+    let mut ts_node_bridge = state
+        .borrow::<Rc<RefCell<bridge::TsNodeBridge>>>()
+        .borrow_mut();
+    let safe_raw_ts_node = OpSafeRawTSNode::from_tsn_bridge(&ts_node_bridge, node_id)?;
+
+    let timeout = state
+        .borrow::<Rc<RefCell<runtime::ExecutionDeadline>>>()
+        .borrow()
+        .remaining();
+
+    let mut cursor = ts_query.cursor();
+    let query_matches = cursor
+        .matches(safe_raw_ts_node.to_node(), tree_text, timeout)
+        .filter(|captures| !captures.is_empty())
+        .collect::<Vec<_>>();
+    Some(v8_query_matches(scope, &mut ts_node_bridge, query_matches))
+}
+
+/// Inserts the captured nodes into the `TsNodeBridge`, returning an array with a `{ captures }`
+/// object for each match.
+fn v8_query_matches<'s>(
+    scope: &mut v8::HandleScope<'s>,
+    ts_node_bridge: &mut bridge::TsNodeBridge,
+    query_matches: Vec<QueryMatch<tree_sitter::Node>>,
+) -> v8::Local<'s, v8::Array> {
+    let s_captures = v8_interned(scope, "captures");
+    let array = v8::Array::new(scope, query_matches.len() as i32);
+    for (idx, query_match) in query_matches.into_iter().enumerate() {
+        let v8_captures = v8::Map::new(scope);
+        for capture in query_match {
+            let capture = ts_node_bridge.insert_capture(scope, capture);
+            let name = v8_string(scope, &capture.name);
+            let value: v8::Local<v8::Value> = match capture.contents {
+                TSCaptureContent::Single(nid) => ts_node_bridge
+                    .v8_get(scope, nid)
+                    .expect("node should have been inserted into the bridge")
+                    .into(),
+                TSCaptureContent::Multi(nids) => {
+                    let nodes = v8::Array::new(scope, nids.len() as i32);
+                    for (node_idx, nid) in nids.into_iter().enumerate() {
+                        let node = ts_node_bridge
+                            .v8_get(scope, nid)
+                            .expect("node should have been inserted into the bridge");
+                        nodes.set_index(scope, node_idx as u32, node.into());
+                    }
+                    nodes.into()
+                }
+            };
+            v8_captures.set(scope, name.into(), value);
+        }
+        let v8_match = v8::Object::new(scope);
+        v8_match.set(scope, s_captures.into(), v8_captures.into());
+        array.set_index(scope, idx as u32, v8_match.into());
+    }
+    array
+}
+
 /// Returns a string containing the text that spans a tree-sitter node.
 ///
 /// # Panics
@@ -72,6 +245,103 @@ pub fn op_ts_node_text(state: &OpState, #[smi] node_id: u32) -> Option<String> {
         .map(ToString::to_string)
 }
 
+/// Returns the byte offset within the source text at which the node starts.
+///
+/// If `node_id` doesn't refer to a node in the bridge, `0` is returned. This is only reachable
+/// if a node's `id` has been mutated from JavaScript, which is unsupported.
+#[op2(fast)]
+pub fn op_ts_node_start_byte(state: &OpState, #[smi] node_id: u32) -> u32 {
+    let node_bridge = state.borrow::<Rc<RefCell<bridge::TsNodeBridge>>>().borrow();
+    OpSafeRawTSNode::from_tsn_bridge(&node_bridge, node_id)
+        .map(|safe_raw_ts_node| safe_raw_ts_node.to_node().start_byte() as u32)
+        .unwrap_or_default()
+}
+
+/// Returns the byte offset within the source text at which the node ends (exclusive).
+///
+/// If `node_id` doesn't refer to a node in the bridge, `0` is returned. This is only reachable
+/// if a node's `id` has been mutated from JavaScript, which is unsupported.
+#[op2(fast)]
+pub fn op_ts_node_end_byte(state: &OpState, #[smi] node_id: u32) -> u32 {
+    let node_bridge = state.borrow::<Rc<RefCell<bridge::TsNodeBridge>>>().borrow();
+    OpSafeRawTSNode::from_tsn_bridge(&node_bridge, node_id)
+        .map(|safe_raw_ts_node| safe_raw_ts_node.to_node().end_byte() as u32)
+        .unwrap_or_default()
+}
+
+/// Returns a plain object containing the node's full source range:
+/// ```text
+/// { startByte, endByte, startLine, startCol, endLine, endCol }
+/// ```
+/// Byte offsets are 0-based (the end is exclusive), and lines and columns are 1-based.
+///
+/// This allows a rule to read all six values with a single op call. If the node doesn't exist,
+/// `None` is returned.
+#[op2]
+pub fn op_ts_node_source_range<'s>(
+    state: &OpState,
+    scope: &mut v8::HandleScope<'s>,
+    #[smi] node_id: u32,
+) -> Option<v8::Local<'s, v8::Object>> {
+    let node_bridge = state.borrow::<Rc<RefCell<bridge::TsNodeBridge>>>().borrow();
+    let safe_raw_ts_node = OpSafeRawTSNode::from_tsn_bridge(&node_bridge, node_id)?;
+    let ts_node = safe_raw_ts_node.to_node();
+    let start = ts_node.start_position_1based();
+    let end = ts_node.end_position_1based();
+
+    let object = v8::Object::new(scope);
+    let fields = [
+        ("startByte", ts_node.start_byte() as u32),
+        ("endByte", ts_node.end_byte() as u32),
+        ("startLine", start.line),
+        ("startCol", start.col),
+        ("endLine", end.line),
+        ("endCol", end.col),
+    ];
+    for (name, value) in fields {
+        let key = v8_interned(scope, name);
+        let value = v8_uint(scope, value);
+        object.set(scope, key.into(), value.into());
+    }
+    Some(object)
+}
+
+/// Returns a `Uint8Array` containing the UTF-8 bytes of the source text that the node spans,
+/// without performing any UTF-8 boundary validation on the node's byte range.
+///
+/// These are the bytes of the text that was analyzed, which are not necessarily the bytes of the
+/// file on disk: a file that is not valid UTF-8 is transcoded to UTF-8 before the analysis
+/// (for example, a Latin-1 `é` is returned as `[0xC3, 0xA9]`, not `[0xE9]`).
+///
+/// # Panics
+/// Panics if the [`ddsa_lib::RootContext`](crate::analysis::ddsa_lib::RootContext) has not set the tree's text.
+#[op2]
+pub fn op_ts_node_utf8_text_bytes<'s>(
+    state: &OpState,
+    scope: &mut v8::HandleScope<'s>,
+    #[smi] node_id: u32,
+) -> Option<v8::Local<'s, v8::Uint8Array>> {
+    let ctx_bridge = state
+        .borrow::<Rc<RefCell<bridge::ContextBridge>>>()
+        .borrow();
+    let tree_text = ctx_bridge
+        .ddsa_root_context()
+        .get_text()
+        .expect("tree text should always be `Some` during rule execution");
+    let node_bridge = state.borrow::<Rc<RefCell<bridge::TsNodeBridge>>>().borrow();
+    let safe_raw_ts_node = OpSafeRawTSNode::from_tsn_bridge(&node_bridge, node_id)?;
+    let ts_node = safe_raw_ts_node.to_node();
+    let bytes = tree_text
+        .as_bytes()
+        .get(ts_node.start_byte()..ts_node.end_byte())?
+        .to_vec();
+    let byte_len = bytes.len();
+    // The backing store takes ownership of the `Vec`, so no additional copy is made.
+    let backing_store = v8::ArrayBuffer::new_backing_store_from_vec(bytes).make_shared();
+    let array_buffer = v8::ArrayBuffer::with_backing_store(scope, &backing_store);
+    v8::Uint8Array::new(scope, array_buffer, 0, byte_len)
+}
+
 /// Given a tree-sitter node (via its `node_id`), this function traverses the tree to find the
 /// named children of the node, inserting them into the `TsNodeBridge`.
 ///
@@ -163,6 +433,68 @@ pub fn op_ts_node_parent(
     Some(nid)
 }
 
+/// Returns the number of named children of the node's parent (including the node itself), without
+/// inserting any of them into the `TsNodeBridge`.
+///
+/// If the node has no parent (i.e. the node passed in is the root node), or doesn't exist, `0` is returned.
+#[op2(fast)]
+pub fn op_ts_node_sibling_count(state: &OpState, #[smi] node_id: u32) -> u32 {
+    let ts_node_bridge = state.borrow::<Rc<RefCell<bridge::TsNodeBridge>>>();
+    let ctx_bridge = state.borrow::<Rc<RefCell<bridge::ContextBridge>>>();
+
+    let Some(safe_raw_ts_node) =
+        OpSafeRawTSNode::from_tsn_bridge(&ts_node_bridge.borrow(), node_id)
+    else {
+        return 0;
+    };
+    let ts_node = safe_raw_ts_node.to_node();
+
+    let ctx_bridge = ctx_bridge.borrow();
+    let root_ctx = ctx_bridge.ddsa_root_context();
+    OpSafeRawTSNode::from_root_context(root_ctx, |ctx| ctx.get_ts_node_parent(ts_node))
+        .map(|safe_raw_parent| safe_raw_parent.to_node().named_child_count() as u32)
+        .unwrap_or_default()
+}
+
+/// Given a tree-sitter node (via its `root_id`) and a `/`-delimited path of node kinds (e.g. `"formal_parameters/identifier"`),
+/// this function traverses the tree by repeatedly descending into the first named child matching
+/// each kind, inserting the final node into the `TsNodeBridge`.
+///
+/// If the node doesn't exist, or any step of the path doesn't match a child, `None` is returned.
+#[op2]
+pub fn op_ts_node_by_path<'s>(
+    state: &OpState,
+    scope: &mut v8::HandleScope<'s>,
+    #[smi] root_id: u32,
+    #[string] path: &str,
+) -> Option<v8::Local<'s, v8::Object>> {
+    let ts_node_bridge = state.borrow::<Rc<RefCell<bridge::TsNodeBridge>>>();
+
+    let safe_raw_ts_node = OpSafeRawTSNode::from_tsn_bridge(&ts_node_bridge.borrow(), root_id)?;
+    let mut ts_node = safe_raw_ts_node.to_node();
+    for kind in path.split('/') {
+        ts_node = first_named_child_by_kind(ts_node, kind)?;
+    }
+
+    let mut bridge_ref = ts_node_bridge.borrow_mut();
+    let nid = bridge_ref.insert(scope, ts_node);
+    bridge_ref.v8_get(scope, nid)
+}
+
+/// Returns the first named child of `node` with the provided `kind`, if it exists.
+fn first_named_child_by_kind<'a>(
+    node: tree_sitter::Node<'a>,
+    kind: &str,
+) -> Option<tree_sitter::Node<'a>> {
+    let mut cursor = node.walk();
+    for child in node.named_children(&mut cursor) {
+        if child.kind() == kind {
+            return Some(child);
+        }
+    }
+    None
+}
+
 /// An op that returns the operator ([`BinOp`](ddsa_lib::js::flow::java::BinOp)) for a binary expression,
 /// or `-1` if the provided node either doesn't exist or isn't a "binary_expression".
 #[op2(fast)]
@@ -11,6 +11,7 @@ use crate::analysis::ddsa_lib::common::{
 };
 use crate::analysis::ddsa_lib::js;
 use crate::analysis::ddsa_lib::js::{VisitArgCodeCompat, VisitArgFilenameCompat};
+use crate::analysis::ddsa_lib::metrics::{MetricsBuffer, MetricsSink, NoopMetricsSink};
 use crate::analysis::ddsa_lib::resource_watchdog::V8ResourceWatchdog;
 use crate::model::common::Language;
 use crate::model::rule::RuleInternal;
@@ -27,14 +28,33 @@ const BRIDGE_CONTEXT: &str = "__RUST_BRIDGE__context";
 const BRIDGE_QUERY_MATCH: &str = "__RUST_BRIDGE__query_match";
 const BRIDGE_TS_NODE: &str = "__RUST_BRIDGE__ts_node";
 const BRIDGE_VIOLATION: &str = "__RUST_BRIDGE__violation";
+const FILE_CONTEXT_VARIABLES: &str = "DD_FILE_CONTEXT";
 const STELLA_COMPAT_FILENAME: &str = "STELLA_COMPAT_FILENAME";
 const STELLA_COMPAT_FILE_CONTENTS: &str = "STELLA_COMPAT_FILE_CONTENTS";
 
+/// A minimal rule (along with its source text and tree-sitter query) executed by [`JsRuntime::warm_up`]
+/// to exercise the most common `ddsa_lib` code paths.
+const WARM_UP_SOURCE: &str = "function warmUp(a, b) { return a + b; }";
+const WARM_UP_TS_QUERY: &str = "(formal_parameters (identifier) @param)";
+const WARM_UP_RULE: &str = r#"
+function visit(captures) {
+    const node = captures.get("param");
+    const parent = ddsa.getParent(node);
+    ddsa.getChildren(parent);
+    addError(buildError(node.start.line, node.start.col, node.end.line, node.end.col, node.text));
+}
+"#;
+
 /// The Datadog Static Analyzer JavaScript runtime
 pub struct JsRuntime {
     runtime: deno_core::JsRuntime,
     v8_resource_watchdog: V8ResourceWatchdog,
     console: Rc<RefCell<JsConsole>>,
+    /// The deadline of the in-progress execution, used to bound tree-sitter queries run by ops.
+    execution_deadline: Rc<RefCell<ExecutionDeadline>>,
+    /// Metrics reported by rules, buffered until [`JsRuntime::flush_metrics`] is called.
+    metrics: Rc<RefCell<MetricsBuffer>>,
+    metrics_sink: Box<dyn MetricsSink>,
     bridge_context: Rc<RefCell<ContextBridge>>,
     bridge_query_match: QueryMatchBridge,
     bridge_ts_node: Rc<RefCell<TsNodeBridge>>,
@@ -63,6 +83,9 @@ impl JsRuntime {
             let v8_ctx_obj = context.as_local(scope);
             let key_ctx = v8_interned(scope, BRIDGE_CONTEXT);
             v8_ddsa_object.set(scope, key_ctx.into(), v8_ctx_obj.into());
+            let v8_scope_vars = context.scope_vars_as_local(scope);
+            let key_scope_vars = v8_interned(scope, FILE_CONTEXT_VARIABLES);
+            v8_ddsa_object.set(scope, key_scope_vars.into(), v8_scope_vars.into());
             let context = Rc::new(RefCell::new(context));
 
             let query_match = QueryMatchBridge::try_new(scope)?;
@@ -119,12 +142,21 @@ impl JsRuntime {
         let console = Rc::new(RefCell::new(JsConsole::new()));
         op_state.put(Rc::clone(&console));
 
+        let execution_deadline = Rc::new(RefCell::new(ExecutionDeadline::default()));
+        op_state.put(Rc::clone(&execution_deadline));
+
+        let metrics = Rc::new(RefCell::new(MetricsBuffer::new()));
+        op_state.put(Rc::clone(&metrics));
+
         let v8_resource_watchdog = V8ResourceWatchdog::new(deno_runtime.v8_isolate());
 
         Ok(Self {
             runtime: deno_runtime,
             v8_resource_watchdog,
             console,
+            execution_deadline,
+            metrics,
+            metrics_sink: Box::new(NoopMetricsSink),
             bridge_context: context,
             bridge_query_match: query_match,
             bridge_ts_node: ts_node,
@@ -136,6 +168,32 @@ impl JsRuntime {
     }
 
     pub fn execute_rule(
+        &mut self,
+        source_text: &Arc<str>,
+        source_tree: &Arc<tree_sitter::Tree>,
+        file_name: &Arc<str>,
+        rule: &RuleInternal,
+        rule_arguments: &HashMap<String, String>,
+        timeout: Option<Duration>,
+    ) -> Result<ExecutionResult, DDSAJsRuntimeError> {
+        // The rule id is only exposed to JavaScript for the duration of the execution.
+        self.bridge_context
+            .borrow_mut()
+            .set_rule_id(Some(rule.name.as_str()));
+        let res = self.execute_rule_with_id(
+            source_text,
+            source_tree,
+            file_name,
+            rule,
+            rule_arguments,
+            timeout,
+        );
+        self.bridge_context.borrow_mut().set_rule_id(None);
+        res
+    }
+
+    /// Executes the rule, assuming the rule id has already been set on the [`ContextBridge`].
+    fn execute_rule_with_id(
         &mut self,
         source_text: &Arc<str>,
         source_tree: &Arc<tree_sitter::Tree>,
@@ -147,7 +205,11 @@ impl JsRuntime {
         let script_cache = Rc::clone(&self.script_cache);
         let mut script_cache_ref = script_cache.borrow_mut();
         if !script_cache_ref.contains_key(&rule.name) {
-            let rule_script = Self::format_rule_script(&rule.code);
+            let rule_script = if rule.tree_sitter_query.is_some() {
+                Self::format_rule_script(&rule.code)
+            } else {
+                Self::format_file_rule_script(&rule.code, rule.language)
+            };
             let script = compile_script(&mut self.runtime.handle_scope(), &rule_script)?;
             script_cache_ref.insert(rule.name.clone(), script);
         }
@@ -155,11 +217,32 @@ impl JsRuntime {
             .get(&rule.name)
             .expect("cache should have been populated");
 
+        let Some(tree_sitter_query) = &rule.tree_sitter_query else {
+            let now = Instant::now();
+            let js_violations = self.execute_file_rule_internal(
+                source_text,
+                source_tree,
+                file_name,
+                rule.language,
+                rule_script,
+                rule_arguments,
+                timeout,
+            )?;
+            return Ok(self.build_execution_result(
+                rule,
+                js_violations,
+                ExecutionTimingCompat {
+                    ts_query: Duration::ZERO,
+                    execution: now.elapsed(),
+                },
+            ));
+        };
+
         let now = Instant::now();
 
         let ts_query_cursor = Rc::clone(&self.ts_query_cursor);
         let mut ts_qc = ts_query_cursor.borrow_mut();
-        let mut query_cursor = rule.tree_sitter_query.with_cursor(&mut ts_qc);
+        let mut query_cursor = tree_sitter_query.with_cursor(&mut ts_qc);
         let query_matches = query_cursor
             .matches(source_tree.root_node(), source_text.as_ref(), timeout)
             .filter(|captures| !captures.is_empty())
@@ -194,21 +277,84 @@ impl JsRuntime {
 
         let execution_time = now.elapsed();
 
-        let violations = js_violations
-            .into_iter()
-            .map(|v| v.into_violation(rule.severity, rule.category))
-            .collect::<Vec<_>>();
-
         let timing = ExecutionTimingCompat {
             ts_query: ts_query_time,
             execution: execution_time,
         };
+        Ok(self.build_execution_result(rule, js_violations, timing))
+    }
+
+    /// Converts the violations from an execution into an [`ExecutionResult`], draining the console.
+    fn build_execution_result(
+        &self,
+        rule: &RuleInternal,
+        js_violations: Vec<js::Violation<Instance>>,
+        timing: ExecutionTimingCompat,
+    ) -> ExecutionResult {
+        let violations = js_violations
+            .into_iter()
+            .map(|v| v.into_violation(rule.severity, rule.category))
+            .collect::<Vec<_>>();
         let console_lines = self.console.borrow_mut().drain().collect::<Vec<_>>();
-        Ok(ExecutionResult {
+        ExecutionResult {
             violations,
             console_lines,
             timing,
-        })
+        }
+    }
+
+    /// Sets the [`MetricsSink`] that buffered metrics will be flushed to.
+    pub fn set_metrics_sink(&mut self, sink: Box<dyn MetricsSink>) {
+        self.metrics_sink = sink;
+    }
+
+    /// Flushes all metrics reported since the last flush to the runtime's [`MetricsSink`].
+    /// This should be called once the scan of `file_name` has completed.
+    pub fn flush_metrics(&mut self, file_name: &str) {
+        let metrics = self.metrics.borrow_mut().take();
+        if !metrics.is_empty() {
+            self.metrics_sink.flush(file_name, metrics);
+        }
+    }
+
+    /// Executes a minimal no-op rule once, triggering v8's compilation of the most common
+    /// JavaScript code paths so that the first "real" rule execution isn't penalized.
+    ///
+    /// The rule's output is discarded, and the script cache is not modified.
+    pub fn warm_up(&mut self) -> Result<(), DDSAJsRuntimeError> {
+        let language = Language::JavaScript;
+        let source_text: Arc<str> = Arc::from(WARM_UP_SOURCE);
+        let file_name: Arc<str> = Arc::from("warm-up.js");
+        let source_tree = analysis::tree_sitter::get_tree(WARM_UP_SOURCE, &language)
+            .expect("warm-up source should be parseable");
+        let source_tree = Arc::new(source_tree);
+        let ts_lang = analysis::tree_sitter::get_tree_sitter_language(&language);
+        let ts_query = analysis::tree_sitter::TSQuery::try_new(&ts_lang, WARM_UP_TS_QUERY)
+            .expect("warm-up query should be valid");
+
+        let rule_script = Self::format_rule_script(WARM_UP_RULE);
+        let rule_script = compile_script(&mut self.runtime.handle_scope(), &rule_script)?;
+
+        let ts_query_cursor = Rc::clone(&self.ts_query_cursor);
+        let mut ts_qc = ts_query_cursor.borrow_mut();
+        let mut query_cursor = ts_query.with_cursor(&mut ts_qc);
+        let query_matches = query_cursor
+            .matches(source_tree.root_node(), source_text.as_ref(), None)
+            .filter(|captures| !captures.is_empty())
+            .collect::<Vec<_>>();
+
+        self.execute_rule_internal(
+            &source_text,
+            &source_tree,
+            &file_name,
+            language,
+            &rule_script,
+            &query_matches,
+            &HashMap::new(),
+            None,
+        )?;
+        self.console.borrow_mut().clear();
+        Ok(())
     }
 
     /// Clears the [`v8::UnboundScript`] cache for the given rule name, returning `true` if a script
@@ -237,22 +383,15 @@ impl JsRuntime {
                 return Ok(vec![]);
             }
 
-            let scope = &mut self.runtime.handle_scope();
-
-            // Push data from Rust to v8
-            // Update the DDSA context metadata
-            let mut ctx_bridge = self.bridge_context.borrow_mut();
-            let was_new_tree =
-                ctx_bridge.set_root_context(scope, source_tree, source_text, file_name);
-            if was_new_tree {
-                // If the tree was new, clear the TsNodeBridge, as it contains nodes for the old tree.
-                self.bridge_ts_node.borrow_mut().clear(scope);
-                // Set the file context
-                ctx_bridge.set_file_context(scope, language, source_tree, source_text);
-            }
+            self.set_execution_context(
+                source_text,
+                source_tree,
+                file_name,
+                language,
+                rule_arguments,
+            );
 
-            // Add any rule arguments
-            ctx_bridge.set_rule_arguments(scope, rule_arguments);
+            let scope = &mut self.runtime.handle_scope();
             // Push the query matches:
             let mut ts_node_bridge = self.bridge_ts_node.borrow_mut();
             self.bridge_query_match
@@ -278,6 +417,64 @@ impl JsRuntime {
         }
     }
 
+    /// Executes a [`RuleType::JavaScript`](crate::model::rule::RuleType::JavaScript) rule, which is
+    /// invoked once for the entire file instead of once per tree-sitter query match.
+    fn execute_file_rule_internal(
+        &mut self,
+        source_text: &Arc<str>,
+        source_tree: &Arc<tree_sitter::Tree>,
+        file_name: &Arc<str>,
+        language: Language,
+        rule_script: &v8::Global<v8::UnboundScript>,
+        rule_arguments: &HashMap<String, String>,
+        timeout: Option<Duration>,
+    ) -> Result<Vec<js::Violation<Instance>>, DDSAJsRuntimeError> {
+        self.set_execution_context(
+            source_text,
+            source_tree,
+            file_name,
+            language,
+            rule_arguments,
+        );
+
+        let execution_res = self.scoped_execute(rule_script, |_, _| (), timeout);
+
+        let violations_res = self
+            .bridge_violation
+            .drain_collect(&mut self.runtime.handle_scope());
+
+        if let Err(runtime_err) = execution_res {
+            Err(runtime_err)
+        } else {
+            violations_res
+        }
+    }
+
+    /// Pushes the metadata about the file and rule from Rust to v8.
+    fn set_execution_context(
+        &mut self,
+        source_text: &Arc<str>,
+        source_tree: &Arc<tree_sitter::Tree>,
+        file_name: &Arc<str>,
+        language: Language,
+        rule_arguments: &HashMap<String, String>,
+    ) {
+        let scope = &mut self.runtime.handle_scope();
+
+        // Update the DDSA context metadata
+        let mut ctx_bridge = self.bridge_context.borrow_mut();
+        let was_new_tree = ctx_bridge.set_root_context(scope, source_tree, source_text, file_name);
+        if was_new_tree {
+            // If the tree was new, clear the TsNodeBridge, as it contains nodes for the old tree.
+            self.bridge_ts_node.borrow_mut().clear(scope);
+            // Set the file context
+            ctx_bridge.set_file_context(scope, language, source_tree, source_text);
+        }
+
+        // Add any rule arguments
+        ctx_bridge.set_rule_arguments(scope, rule_arguments);
+    }
+
     /// Executes a given script within the DDSA runtime context.
     ///
     /// An optional `timeout` can be specified to limit the length the JavaScript script may run for.
@@ -327,9 +524,12 @@ impl JsRuntime {
         let opened = script.open(tc_ctx_scope);
         let bound_script = opened.bind_to_current_context(tc_ctx_scope);
 
+        *self.execution_deadline.borrow_mut() = ExecutionDeadline::after(timeout);
         let execution_result = self
             .v8_resource_watchdog
-            .execute(timeout, tc_ctx_scope, |sc| bound_script.run(sc))?;
+            .execute(timeout, tc_ctx_scope, |sc| bound_script.run(sc));
+        *self.execution_deadline.borrow_mut() = ExecutionDeadline::default();
+        let execution_result = execution_result?;
 
         let return_val = execution_result.ok_or_else(|| {
             let exception = tc_ctx_scope
@@ -368,6 +568,32 @@ for (const queryMatch of globalThis.__RUST_BRIDGE__query_match) {{
         )
     }
 
+    /// Wraps the `rule_code` of a [`RuleType::JavaScript`](crate::model::rule::RuleType::JavaScript) rule
+    /// with the necessary DDSA hooks to pass and receive data from Rust to v8.
+    fn format_file_rule_script(rule_code: &str, language: Language) -> String {
+        format!(
+            "\
+'use strict';
+
+(() => {{
+
+// The rule's JavaScript code
+//////////////////////////////
+{}
+//////////////////////////////
+
+visit({{
+    fileContent: globalThis.__RUST_BRIDGE__context.fileContents,
+    fileName: globalThis.__RUST_BRIDGE__context.filename,
+    language: \"{}\",
+}});
+
+}})();
+",
+            rule_code, language
+        )
+    }
+
     /// Provides a [`v8::HandleScope`] for the underlying v8 isolate.
     #[cfg(test)]
     pub fn v8_handle_scope(&mut self) -> v8::HandleScope {
@@ -379,6 +605,20 @@ for (const queryMatch of globalThis.__RUST_BRIDGE__query_match) {{
         Rc::clone(&self.bridge_ts_node)
     }
 
+    /// Clears the runtime's [`TsNodeBridge`], and then inserts each of the `nodes` into it.
+    ///
+    /// This is only exposed for the benchmarks.
+    #[cfg(feature = "bench")]
+    #[doc(hidden)]
+    pub fn bench_insert_ts_nodes(&mut self, nodes: &[tree_sitter::Node]) {
+        let scope = &mut self.runtime.handle_scope();
+        let mut bridge = self.bridge_ts_node.borrow_mut();
+        bridge.clear(scope);
+        for node in nodes {
+            bridge.insert(scope, *node);
+        }
+    }
+
     /// Returns the length of the `v8::Array` backing the runtime's `ViolationBridge`.
     #[cfg(test)]
     pub fn violation_bridge_v8_len(&mut self) -> usize {
@@ -393,6 +633,11 @@ for (const queryMatch of globalThis.__RUST_BRIDGE__query_match) {{
         Rc::clone(&self.bridge_context)
     }
 
+    #[cfg(test)]
+    pub(crate) fn metrics_buffer(&self) -> Rc<RefCell<MetricsBuffer>> {
+        Rc::clone(&self.metrics)
+    }
+
     /// Drains and returns the lines from the DDSA console.
     #[allow(unused)]
     #[cfg(test)]
@@ -541,6 +786,29 @@ impl JsConsole {
     }
 }
 
+/// The point in time at which the in-progress JavaScript execution will be terminated, if it has a timeout.
+#[derive(Debug, Default, Copy, Clone)]
+pub(crate) struct ExecutionDeadline(Option<Instant>);
+
+impl ExecutionDeadline {
+    /// Creates a deadline `timeout` from now (or no deadline, if `timeout` is `None`).
+    pub fn after(timeout: Option<Duration>) -> Self {
+        Self(timeout.map(|timeout| Instant::now() + timeout))
+    }
+
+    /// Returns the time remaining until the deadline, or `None` if there is no deadline.
+    ///
+    /// If the deadline has passed, the smallest non-zero duration is returned, because a zero
+    /// timeout is interpreted by tree-sitter as "no timeout".
+    pub fn remaining(&self) -> Option<Duration> {
+        self.0.map(|deadline| {
+            deadline
+                .saturating_duration_since(Instant::now())
+                .max(Duration::from_micros(1))
+        })
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use crate::analysis::ddsa_lib::common::{
@@ -650,6 +918,7 @@ assert(globalThis.__RUST_BRIDGE__context instanceof RootContext, "ContextBridge
 assert(Array.isArray(globalThis.__RUST_BRIDGE__query_match), "QueryMatchBridge global has wrong type");
 assert(typeof globalThis.__RUST_BRIDGE__ts_node === "object", "TsNodeBridge global has wrong type");
 assert(Array.isArray(globalThis.__RUST_BRIDGE__violation), "ViolationBridge global has wrong type");
+assert(typeof globalThis.DD_FILE_CONTEXT === "object", "File context variables global has wrong type");
 // An arbitrary return value to confirm that the execution completed without throwing:
 123;
 "#;
@@ -890,6 +1159,43 @@ function visit(captures) {
         assert!(matches!(err, DDSAJsRuntimeError::TreeSitterTimeout { .. }));
     }
 
+    /// A tree-sitter query run by a rule via `ddsa.queryMatches` is bounded by the execution's timeout.
+    #[test]
+    fn nested_query_execute_timeout() {
+        let mut runtime = cfg_test_v8().new_runtime();
+        let timeout = Duration::from_millis(500);
+        let code = "function foo() { const baz = 1; }".repeat(100000);
+        // (See `query_execute_timeout` for why this query is expensive).
+        let rule_code = r#"
+function visit(captures) {
+    ddsa.queryMatches(`
+(
+  (function_declaration body: (statement_block (lexical_declaration))) @foo
+  (function_declaration body: (statement_block (lexical_declaration))) @foo
+  (function_declaration body: (statement_block (lexical_declaration))) @foo
+)`);
+}
+"#;
+        let options = ExecuteOptions {
+            file_name: None,
+            rule_arguments: None,
+            timeout: Some(timeout),
+        };
+
+        let now = Instant::now();
+        let err = shorthand_execute_rule(
+            &mut runtime,
+            Language::JavaScript,
+            "(program) @program",
+            rule_code,
+            &code,
+            Some(options),
+        )
+        .expect_err("Expected a timeout error");
+        assert!(matches!(err, DDSAJsRuntimeError::JavaScriptTimeout { .. }));
+        assert!(now.elapsed() < timeout * 10);
+    }
+
     /// `scoped_execute` can terminate JavaScript execution that goes on for too long.
     #[test]
     fn scoped_execute_timeout() {
@@ -918,6 +1224,273 @@ function visit(captures) {
         assert!(matches!(err, DDSAJsRuntimeError::JavaScriptMemoryExceeded));
     }
 
+    /// A `RuleType::JavaScript` rule is executed once per file with the file's metadata, without a tree-sitter query.
+    #[test]
+    fn execute_file_rule() {
+        use crate::model::rule::{RuleCategory, RuleInternal, RuleSeverity};
+        let mut rt = cfg_test_v8().new_runtime();
+        let text: Arc<str> = Arc::from("const a = 1;\n// TODO: remove\nconst b = 2; // TODO\n");
+        let tree = Arc::new(get_tree(&text, &Language::JavaScript).unwrap());
+        let filename: Arc<str> = Arc::from("file.js");
+        let code = r#"
+function visit(file) {
+    console.log(file.fileName, file.language);
+    file.fileContent.split("\n").forEach((line, idx) => {
+        for (const match of line.matchAll(/TODO/g)) {
+            const col = match.index + 1;
+            addError(buildError(idx + 1, col, idx + 1, col + match[0].length, "found a TODO"));
+        }
+    });
+}
+"#;
+        let rule = RuleInternal {
+            name: "javascript-rule".to_string(),
+            short_description: None,
+            description: None,
+            category: RuleCategory::BestPractices,
+            severity: RuleSeverity::Warning,
+            language: Language::JavaScript,
+            code: code.to_string(),
+            tree_sitter_query: None,
+        };
+        let res = rt
+            .execute_rule(&text, &tree, &filename, &rule, &HashMap::new(), None)
+            .unwrap();
+        assert_eq!(res.console_lines, vec!["file.js javascript".to_string()]);
+        let positions = res
+            .violations
+            .iter()
+            .map(|v| (v.start.line, v.start.col))
+            .collect::<Vec<_>>();
+        assert_eq!(positions, vec![(2, 4), (3, 17)]);
+    }
+
+    /// `warm_up` executes successfully on a fresh runtime without leaving behind any state that
+    /// would affect a subsequent rule execution.
+    #[test]
+    fn warm_up_leaves_no_state() {
+        let mut rt = cfg_test_v8().new_runtime();
+        rt.warm_up().unwrap();
+        assert!(rt.console.borrow().0.is_empty());
+        assert!(rt.script_cache.borrow().is_empty());
+
+        let text = "const abc = 123;";
+        let ts_query = "(identifier) @cap";
+        let code = r#"
+function visit(captures) {
+    const node = captures.get("cap");
+    console.log(node.text);
+}
+"#;
+        let res = shorthand_execute_rule(&mut rt, Language::JavaScript, ts_query, code, text, None)
+            .unwrap();
+        assert!(res.violations.is_empty());
+        assert_eq!(res.console_lines, vec!["abc".to_string()]);
+    }
+
+    /// Code flows added via `Violation.addCodeFlow` are attached to the violation they were added to.
+    #[test]
+    fn execute_rule_code_flows() {
+        use crate::model::violation::CodeFlowLocation;
+        let mut rt = cfg_test_v8().new_runtime();
+        let text = "const input = getInput();\neval(input);\neval(input);";
+        let ts_query = "(call_expression function: (identifier) @fn (#eq? @fn \"eval\")) @call";
+        let code = r#"
+function visit(captures) {
+    const node = captures.get("call");
+    addError(
+        Violation.new("eval", node).addCodeFlow([
+            { file: "src/index.js", line: 1, col: 15, message: "user input is read" },
+            { file: "src/index.js", line: node.start.line, col: node.start.col, message: "user input is evaluated" },
+        ])
+    );
+}
+"#;
+        let res = shorthand_execute_rule(&mut rt, Language::JavaScript, ts_query, code, text, None)
+            .unwrap();
+        let flow_to = |line: u32| {
+            vec![
+                CodeFlowLocation {
+                    file: "src/index.js".to_string(),
+                    line: 1,
+                    col: 15,
+                    message: "user input is read".to_string(),
+                },
+                CodeFlowLocation {
+                    file: "src/index.js".to_string(),
+                    line,
+                    col: 1,
+                    message: "user input is evaluated".to_string(),
+                },
+            ]
+        };
+        let code_flows = res
+            .violations
+            .iter()
+            .map(|v| v.code_flows.clone())
+            .collect::<Vec<_>>();
+        assert_eq!(code_flows, vec![vec![flow_to(2)], vec![flow_to(3)]]);
+
+        let code = r#"
+function visit(captures) {
+    addError(Violation.new("eval", captures.get("call")));
+}
+"#;
+        let res = shorthand_execute_rule(&mut rt, Language::JavaScript, ts_query, code, text, None)
+            .unwrap();
+        assert!(res.violations.iter().all(|v| v.code_flows.is_empty()));
+    }
+
+    /// Each rule sees its own id via `ddsa.getRuleId()`, and the id is cleared after execution.
+    #[test]
+    fn execute_rule_current_rule_id() {
+        use crate::model::rule::{RuleCategory, RuleInternal, RuleSeverity};
+        let mut rt = cfg_test_v8().new_runtime();
+        let text: Arc<str> = Arc::from("const abc = 123;");
+        let tree = Arc::new(get_tree(&text, &Language::JavaScript).unwrap());
+        let filename: Arc<str> = Arc::from("file.js");
+        let ts_lang = get_tree_sitter_language(&Language::JavaScript);
+        // Both rules share the same code, which branches on the rule id.
+        let code = r#"
+function visit(captures) {
+    const node = captures.get("cap");
+    if (ddsa.getRuleId() === "js-rules/rule-two") {
+        console.log("rule two:", ddsa.getRuleId());
+    } else {
+        console.log("other rule:", ddsa.getRuleId());
+    }
+}
+"#;
+        let rule = |name: &str| RuleInternal {
+            name: name.to_string(),
+            short_description: None,
+            description: None,
+            category: RuleCategory::BestPractices,
+            severity: RuleSeverity::Warning,
+            language: Language::JavaScript,
+            code: code.to_string(),
+            tree_sitter_query: Some(TSQuery::try_new(&ts_lang, "(identifier) @cap").unwrap()),
+        };
+
+        let res = rt
+            .execute_rule(
+                &text,
+                &tree,
+                &filename,
+                &rule("js-rules/rule-one"),
+                &HashMap::new(),
+                None,
+            )
+            .unwrap();
+        assert_eq!(res.console_lines, vec!["other rule: js-rules/rule-one"]);
+        let res = rt
+            .execute_rule(
+                &text,
+                &tree,
+                &filename,
+                &rule("js-rules/rule-two"),
+                &HashMap::new(),
+                None,
+            )
+            .unwrap();
+        assert_eq!(res.console_lines, vec!["rule two: js-rules/rule-two"]);
+
+        let ctx_bridge = rt.bridge_context.borrow();
+        assert!(ctx_bridge.ddsa_rule_context().get_rule_id().is_none());
+    }
+
+    /// A rule that mutates `DD_LINE_OFFSETS` doesn't affect subsequent rules on the same file.
+    #[test]
+    fn execute_rule_line_offsets_isolated() {
+        use crate::model::rule::{RuleCategory, RuleInternal, RuleSeverity};
+        let mut rt = cfg_test_v8().new_runtime();
+        let text: Arc<str> = Arc::from("const abc = 123;\nconst def = 456;");
+        let tree = Arc::new(get_tree(&text, &Language::JavaScript).unwrap());
+        let filename: Arc<str> = Arc::from("file.js");
+        let rule = |name: &str, code: &str| RuleInternal {
+            name: name.to_string(),
+            short_description: None,
+            description: None,
+            category: RuleCategory::BestPractices,
+            severity: RuleSeverity::Warning,
+            language: Language::JavaScript,
+            code: code.to_string(),
+            tree_sitter_query: None,
+        };
+        let mutating_rule = rule(
+            "js-rules/mutating",
+            r#"
+function visit(file) {
+    DD_LINE_OFFSETS[1] = 999;
+    console.log(Array.from(DD_LINE_OFFSETS).join(","));
+}
+"#,
+        );
+        let reading_rule = rule(
+            "js-rules/reading",
+            r#"
+function visit(file) {
+    console.log(Array.from(DD_LINE_OFFSETS).join(","));
+}
+"#,
+        );
+
+        for (rule, expected) in [(&mutating_rule, "0,999"), (&reading_rule, "0,17")] {
+            let res = rt
+                .execute_rule(&text, &tree, &filename, rule, &HashMap::new(), None)
+                .unwrap();
+            assert_eq!(res.console_lines, vec![expected]);
+        }
+    }
+
+    /// Related locations added via `Violation.addRelatedLocation` are attached to the violation they
+    /// were added to.
+    #[test]
+    fn execute_rule_related_locations() {
+        use crate::model::violation::RelatedLocation;
+        let mut rt = cfg_test_v8().new_runtime();
+        let text = "function foo() {}\nfunction foo() {}";
+        let ts_query = "(function_declaration name: (identifier) @name)";
+        let code = r#"
+function visit(captures) {
+    const node = captures.get("name");
+    const violation = Violation.new("duplicate declaration", node);
+    if (node.start.line === 2) {
+        violation.addRelatedLocation({ message: "first declaration", file: "index.js", line: 1, col: 10 });
+    }
+    addError(violation);
+}
+"#;
+        let res = shorthand_execute_rule(&mut rt, Language::JavaScript, ts_query, code, text, None)
+            .unwrap();
+        let related_locations = res
+            .violations
+            .iter()
+            .map(|v| v.related_locations.clone())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            related_locations,
+            vec![
+                vec![],
+                vec![RelatedLocation {
+                    message: "first declaration".to_string(),
+                    file: "index.js".to_string(),
+                    line: 1,
+                    col: 10,
+                }]
+            ]
+        );
+
+        let code = r#"
+function visit(captures) {
+    const violation = Violation.new("duplicate declaration", captures.get("name"));
+    addError(violation.addRelatedLocation({ message: "missing position", file: "index.js" }));
+}
+"#;
+        let res = shorthand_execute_rule(&mut rt, Language::JavaScript, ts_query, code, text, None);
+        assert!(res.is_err());
+    }
+
     /// `scoped_execute` should always execute with an empty console (despite a previous execution
     /// that didn't explicitly clear the console).
     #[test]
@@ -972,6 +1545,8 @@ function visit(captures) {
             message: "`protectedName` is a protected variable name".to_string(),
             fixes: None,
             taint_flow_regions: None,
+            code_flows: None,
+            related_locations: None,
             _pd: PhantomData,
         };
         assert_eq!(*violation, expected);
@@ -1254,6 +1829,8 @@ function visit(query, filename, code) {
             message: "`protectedName` is a protected variable name".to_string(),
             fixes: None,
             taint_flow_regions: None,
+            code_flows: None,
+            related_locations: None,
             _pd: PhantomData,
         };
         assert_eq!(*violation, expected);
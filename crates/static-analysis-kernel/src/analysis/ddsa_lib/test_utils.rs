@@ -362,7 +362,7 @@ pub(crate) fn shorthand_execute_rule(
         severity: RuleSeverity::Error,
         language,
         code: js_code.to_string(),
-        tree_sitter_query: query,
+        tree_sitter_query: Some(query),
     };
 
     runtime.execute_rule(&source_text, &tree, &filename, &rule, &arguments, timeout)
@@ -84,6 +84,14 @@ impl V8Platform<Initialized> {
         JsRuntime::try_new(make_base_deno_core_runtime(Self::extensions(), None))
     }
 
+    /// Creates and returns a new [`JsRuntime`] that has been [warmed up](JsRuntime::warm_up), so that
+    /// its first rule execution isn't penalized by v8's compilation of the `ddsa_lib` code paths.
+    pub fn try_new_warm_runtime(&self) -> Result<JsRuntime, DDSAJsRuntimeError> {
+        let mut runtime = self.try_new_runtime()?;
+        runtime.warm_up()?;
+        Ok(runtime)
+    }
+
     /// Creates and returns a new [`JsRuntime`] with the provided v8 isolate heap size limit.
     pub fn try_new_runtime_with_heap_limit(
         &self,
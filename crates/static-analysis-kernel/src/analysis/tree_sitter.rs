@@ -8,6 +8,34 @@ use std::time::Duration;
 use streaming_iterator::StreamingIterator;
 use tree_sitter::CaptureQuantifier;
 
+/// Extension methods to get the 1-based position of a [`tree_sitter::Node`].
+///
+/// tree-sitter positions are 0-based, whereas the analyzer reports 1-based lines and columns.
+pub trait NodePositionExt {
+    /// Returns the 1-based start position of the node.
+    fn start_position_1based(&self) -> Position;
+    /// Returns the 1-based end position of the node.
+    fn end_position_1based(&self) -> Position;
+}
+
+impl NodePositionExt for tree_sitter::Node<'_> {
+    fn start_position_1based(&self) -> Position {
+        point_to_1based_position(self.start_position())
+    }
+
+    fn end_position_1based(&self) -> Position {
+        point_to_1based_position(self.end_position())
+    }
+}
+
+/// Converts a 0-based [`tree_sitter::Point`] into a 1-based [`Position`].
+fn point_to_1based_position(point: tree_sitter::Point) -> Position {
+    Position {
+        line: u32::try_from(point.row + 1).unwrap(),
+        col: u32::try_from(point.column + 1).unwrap(),
+    }
+}
+
 pub fn get_tree_sitter_language(language: &Language) -> tree_sitter::Language {
     extern "C" {
         fn tree_sitter_c_sharp() -> tree_sitter::Language;
@@ -351,14 +379,8 @@ pub fn map_node(node: tree_sitter::Node) -> Option<TreeSitterNode> {
         // finally, build the return value.
         let ts_node = TreeSitterNode {
             ast_type: cursor.node().kind().to_string(),
-            start: Position {
-                line: u32::try_from(cursor.node().range().start_point.row + 1).unwrap(),
-                col: u32::try_from(cursor.node().range().start_point.column + 1).unwrap(),
-            },
-            end: Position {
-                line: u32::try_from(cursor.node().range().end_point.row + 1).unwrap(),
-                col: u32::try_from(cursor.node().range().end_point.column + 1).unwrap(),
-            },
+            start: cursor.node().start_position_1based(),
+            end: cursor.node().end_position_1based(),
             field_name: cursor.field_name().map(ToString::to_string),
             children,
         };
@@ -389,6 +411,24 @@ def func():
         assert_eq!("module", t.unwrap().root_node().kind());
     }
 
+    /// Nodes at tree-sitter's origin `(0, 0)` report a 1-based position of `(1, 1)`.
+    #[test]
+    fn test_position_1based_at_origin() {
+        let tree = get_tree("x = 1", &Language::Python).unwrap();
+        let root = tree.root_node();
+        assert_eq!(root.start_position(), tree_sitter::Point::new(0, 0));
+        assert_eq!(root.start_position_1based(), Position::new(1, 1));
+        assert_eq!(root.end_position_1based(), Position::new(1, 6));
+
+        let mapped = map_node(root).unwrap();
+        assert_eq!(mapped.start_position_1based(), Position::new(1, 1));
+        assert_eq!(mapped.end_position_1based(), Position::new(1, 6));
+        let identifier = &mapped.children[0].children[0].children[0];
+        assert_eq!(identifier.ast_type, "identifier");
+        assert_eq!(identifier.start_position_1based(), Position::new(1, 1));
+        assert_eq!(identifier.end_position_1based(), Position::new(1, 2));
+    }
+
     #[test]
     fn test_map_node_simple() {
         let source_code = r#"
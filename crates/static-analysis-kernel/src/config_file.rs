@@ -1,4 +1,4 @@
-use anyhow::Result;
+use anyhow::{bail, Result};
 use indexmap::IndexMap;
 use serde::de::value::MapAccessDeserializer;
 use serde::de::{Error, MapAccess, Unexpected, Visitor};
@@ -11,13 +11,74 @@ use std::marker::PhantomData;
 
 use crate::model::config_file::{
     join_path, split_path, BySubtree, ConfigFile, PathConfig, PathPattern, RuleConfig,
-    RulesetConfig,
+    RuleOverride, RulesetConfig,
 };
 use crate::model::rule::{RuleCategory, RuleSeverity};
 
 pub fn parse_config_file(config_contents: &str) -> Result<ConfigFile> {
-    let yaml_config: YamlConfigFile = serde_yaml::from_str(config_contents)?;
-    Ok(yaml_config.into())
+    let yaml_config: YamlConfigFile = if needs_v1_migration(config_contents)? {
+        serde_yaml::from_str(&migrate_v1_to_v2(config_contents)?)?
+    } else {
+        serde_yaml::from_str(config_contents)?
+    };
+    let config: ConfigFile = yaml_config.into();
+    // A required ruleset must also be enabled, otherwise it would never be resolved.
+    if let Some(rs) = config
+        .required_rulesets
+        .iter()
+        .find(|rs| !config.rulesets.contains_key(*rs))
+    {
+        bail!("required ruleset `{rs}` is not listed in `rulesets`");
+    }
+    Ok(config)
+}
+
+// Rewrites a v1 configuration file as the equivalent v2 file. In v2, the legacy top-level
+// `ignore-paths` list is merged into `ignore`.
+pub fn migrate_v1_to_v2(raw: &str) -> Result<String> {
+    let mut config: Value = serde_yaml::from_str(raw)?;
+    let Some(fields) = config.as_mapping_mut() else {
+        bail!("the configuration file is not a map");
+    };
+    if let Some(ignore_paths) = fields.remove("ignore-paths") {
+        let Value::Sequence(ignore_paths) = ignore_paths else {
+            bail!("`ignore-paths` is not a list");
+        };
+        let ignore = fields
+            .entry(Value::from("ignore"))
+            .or_insert_with(|| Value::Sequence(vec![]));
+        let Some(ignore) = ignore.as_sequence_mut() else {
+            bail!("`ignore` is not a list");
+        };
+        ignore.extend(ignore_paths);
+    }
+    fields.insert(Value::from("schema-version"), Value::from("v2"));
+    Ok(serde_yaml::to_string(&config)?)
+}
+
+// Returns whether the configuration file is a v1 file that uses fields removed in v2.
+// Any other v1 file is also a valid v2 file, so it's parsed as-is to keep the line numbers in
+// error messages accurate. A removed field in a file that isn't v1 is an error, because it would
+// otherwise be silently ignored.
+fn needs_v1_migration(raw: &str) -> Result<bool> {
+    // (A file that isn't a valid map is reported when it's actually parsed)
+    let Ok(Value::Mapping(fields)) = serde_yaml::from_str::<Value>(raw) else {
+        return Ok(false);
+    };
+    if !fields.contains_key("ignore-paths") {
+        return Ok(false);
+    }
+    match fields.get("schema-version").map(Value::as_str) {
+        None | Some(Some("v1")) => Ok(true),
+        Some(Some(version)) => {
+            bail!("`ignore-paths` is not supported in {version} configuration files, use `ignore` instead")
+        }
+        Some(None) => {
+            bail!(
+                "`ignore-paths` is only supported in v1 configuration files, use `ignore` instead"
+            )
+        }
+    }
 }
 
 pub fn config_file_to_yaml(cfg: &ConfigFile) -> Result<String> {
@@ -35,31 +96,32 @@ struct YamlConfigFile {
     #[serde(flatten)]
     paths: YamlPathConfig,
     #[serde(skip_serializing_if = "Option::is_none")]
-    ignore_paths: Option<Vec<String>>,
-    #[serde(skip_serializing_if = "Option::is_none")]
     ignore_gitignore: Option<bool>,
     #[serde(skip_serializing_if = "Option::is_none")]
     max_file_size_kb: Option<u64>,
     #[serde(skip_serializing_if = "Option::is_none")]
     ignore_generated_files: Option<bool>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    required_rulesets: Vec<String>,
+    #[serde(default, skip_serializing_if = "UniqueKeyMap::is_empty")]
+    global_rule_overrides: UniqueKeyMap<YamlRuleOverride>,
 }
 
 impl From<YamlConfigFile> for ConfigFile {
     fn from(value: YamlConfigFile) -> Self {
         ConfigFile {
             rulesets: value.rulesets.into(),
-            paths: {
-                let mut paths: PathConfig = value.paths.into();
-                if let Some(ignore) = value.ignore_paths {
-                    paths
-                        .ignore
-                        .extend(ignore.into_iter().map(PathPattern::from));
-                }
-                paths
-            },
+            paths: value.paths.into(),
             ignore_gitignore: value.ignore_gitignore,
             max_file_size_kb: value.max_file_size_kb,
             ignore_generated_files: value.ignore_generated_files,
+            required_rulesets: value.required_rulesets,
+            global_rule_overrides: value
+                .global_rule_overrides
+                .0
+                .into_iter()
+                .map(|(k, v)| (k, v.into()))
+                .collect(),
         }
     }
 }
@@ -67,24 +129,34 @@ impl From<YamlConfigFile> for ConfigFile {
 impl From<ConfigFile> for YamlConfigFile {
     fn from(value: ConfigFile) -> Self {
         YamlConfigFile {
+            // Files are written as v1 (which they're also valid as), so that older versions of the
+            // analyzer can read them.
             schema_version: YamlSchemaVersion::V1,
             rulesets: value.rulesets.into(),
             paths: value.paths.into(),
-            ignore_paths: None,
             ignore_gitignore: value.ignore_gitignore,
             max_file_size_kb: value.max_file_size_kb,
             ignore_generated_files: value.ignore_generated_files,
+            required_rulesets: value.required_rulesets,
+            global_rule_overrides: UniqueKeyMap(
+                value
+                    .global_rule_overrides
+                    .into_iter()
+                    .map(|(k, v)| (k, v.into()))
+                    .collect(),
+            ),
         }
     }
 }
 
 // YAML-serializable schema version.
-// It only contains the expected value for this parser.
+// It only contains the values expected by this parser.
 #[derive(Serialize, Deserialize, Default)]
 #[serde(rename_all = "kebab-case")]
 enum YamlSchemaVersion {
     #[default]
     V1,
+    V2,
 }
 
 // YAML-serializable ruleset list.
@@ -293,6 +365,8 @@ struct YamlRuleConfig {
     severity: Option<YamlBySubtree<RuleSeverity>>,
     #[serde(skip_serializing_if = "Option::is_none")]
     category: Option<YamlRuleCategory>,
+    #[serde(rename = "custom-tags", default, skip_serializing_if = "Vec::is_empty")]
+    custom_tags: Vec<String>,
 }
 
 impl From<YamlRuleConfig> for RuleConfig {
@@ -307,6 +381,7 @@ impl From<YamlRuleConfig> for RuleConfig {
                 .collect(),
             severity: value.severity.map(YamlBySubtree::into),
             category: value.category.map(|c| c.0),
+            custom_tags: value.custom_tags,
         }
     }
 }
@@ -324,6 +399,38 @@ impl From<RuleConfig> for YamlRuleConfig {
             ),
             severity: value.severity.map(BySubtree::into),
             category: value.category.map(YamlRuleCategory),
+            custom_tags: value.custom_tags,
+        }
+    }
+}
+
+// YAML-serializable override for a rule.
+#[derive(Deserialize, Serialize, Default, PartialEq)]
+struct YamlRuleOverride {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    severity: Option<RuleSeverity>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    category: Option<YamlRuleCategory>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    enabled: Option<bool>,
+}
+
+impl From<YamlRuleOverride> for RuleOverride {
+    fn from(value: YamlRuleOverride) -> Self {
+        RuleOverride {
+            severity: value.severity,
+            category: value.category.map(|c| c.0),
+            enabled: value.enabled,
+        }
+    }
+}
+
+impl From<RuleOverride> for YamlRuleOverride {
+    fn from(value: RuleOverride) -> Self {
+        YamlRuleOverride {
+            severity: value.severity,
+            category: value.category.map(YamlRuleCategory),
+            enabled: value.enabled,
         }
     }
 }
@@ -760,6 +867,7 @@ rulesets:
                             arguments: IndexMap::new(),
                             severity: None,
                             category: None,
+                            custom_tags: vec![],
                         },
                     )]),
                 },
@@ -771,6 +879,27 @@ rulesets:
         assert_eq!(expected, res.unwrap());
     }
 
+    // Custom tags can be parsed and serialized.
+    #[test]
+    fn test_parse_custom_tags() {
+        let data = r#"
+schema-version: v1
+rulesets:
+- python-security: null
+  rules:
+    no-eval:
+      custom-tags:
+      - team:security
+      - jira-project:SEC
+"#;
+        let res = parse_config_file(data).unwrap();
+        let rule = &res.rulesets["python-security"].rules["no-eval"];
+        assert_eq!(rule.custom_tags, vec!["team:security", "jira-project:SEC"]);
+
+        let serialized = config_file_to_yaml(&res).unwrap();
+        assert_eq!(parse_config_file(&serialized).unwrap(), res);
+    }
+
     // Rules cannot be specified as lists of strings or maps.
     #[test]
     fn test_cannot_parse_rules_as_list() {
@@ -867,6 +996,7 @@ rulesets:
                                 ]),
                                 severity: None,
                                 category: None,
+                                custom_tags: vec![],
                             },
                         ),
                         (
@@ -885,6 +1015,7 @@ rulesets:
                                 ]),
                                 severity: None,
                                 category: None,
+                                custom_tags: vec![],
                             },
                         ),
                     ]),
@@ -926,12 +1057,167 @@ max-file-size-kb: 512
             ignore_gitignore: Some(false),
             max_file_size_kb: Some(512),
             ignore_generated_files: None,
+            required_rulesets: vec![],
+            global_rule_overrides: IndexMap::new(),
         };
 
         let res = parse_config_file(data);
         assert_eq!(expected, res.unwrap());
     }
 
+    // A v1 file is migrated to v2, and parses to the same configuration as its v2 equivalent.
+    #[test]
+    fn test_migrate_v1_to_v2() {
+        let v1 = r#"
+schema-version: v1
+rulesets:
+  - python-security
+ignore:
+  - "py/testing/*.py"
+ignore-paths:
+  - "**/test/**"
+  - path1
+    "#;
+        let v2 = r#"
+schema-version: v2
+rulesets:
+  - python-security
+ignore:
+  - "py/testing/*.py"
+  - "**/test/**"
+  - path1
+    "#;
+        let migrated = migrate_v1_to_v2(v1).unwrap();
+        assert!(migrated.contains("schema-version: v2"));
+        assert!(!migrated.contains("ignore-paths"));
+        let expected = parse_config_file(v2).unwrap();
+        assert_eq!(parse_config_file(&migrated).unwrap(), expected);
+        assert_eq!(parse_config_file(v1).unwrap(), expected);
+
+        // Without a version, a file is v1, and the legacy field is moved to a new `ignore` list.
+        let v1 = r#"
+rulesets:
+  - python-security
+ignore-paths:
+  - path1
+    "#;
+        let res = parse_config_file(v1).unwrap();
+        assert_eq!(
+            res.paths.ignore,
+            vec![PathPattern::from("path1".to_string())]
+        );
+
+        let v1 = r#"
+rulesets:
+  - python-security
+ignore-paths: path1
+    "#;
+        assert!(parse_config_file(v1).is_err());
+
+        // The legacy field isn't silently dropped from files of other versions.
+        let v2 = r#"
+schema-version: v2
+rulesets:
+  - python-security
+ignore-paths:
+  - path1
+    "#;
+        let err = parse_config_file(v2).unwrap_err();
+        assert!(err
+            .to_string()
+            .contains("`ignore-paths` is not supported in v2 configuration files"));
+        let invalid_version = r#"
+schema-version: 2
+rulesets:
+  - python-security
+ignore-paths:
+  - path1
+    "#;
+        let err = parse_config_file(invalid_version).unwrap_err();
+        assert!(err
+            .to_string()
+            .contains("`ignore-paths` is only supported in v1 configuration files"));
+    }
+
+    // Required rulesets are parsed, and must be listed in `rulesets`.
+    #[test]
+    fn test_parse_required_rulesets() {
+        let data = r#"
+rulesets:
+  - python-security
+  - python-best-practices
+required-rulesets:
+  - python-security
+    "#;
+        let res = parse_config_file(data).unwrap();
+        assert_eq!(res.required_rulesets, vec!["python-security"]);
+        assert!(res
+            .missing_required_rulesets(&["python-security", "python-best-practices"])
+            .is_empty());
+        assert_eq!(
+            res.missing_required_rulesets(&["python-best-practices"]),
+            vec!["python-security"]
+        );
+
+        let data = r#"
+rulesets:
+  - python-best-practices
+required-rulesets:
+  - python-security
+    "#;
+        let err = parse_config_file(data).unwrap_err();
+        assert!(err
+            .to_string()
+            .contains("required ruleset `python-security` is not listed in `rulesets`"));
+    }
+
+    // Global rule overrides are parsed and survive a serialization round trip.
+    #[test]
+    fn test_parse_global_rule_overrides() {
+        let data = r#"
+rulesets:
+  - python-security
+global-rule-overrides:
+  python-security/no-eval:
+    severity: ERROR
+    category: SECURITY
+  python-best-practices/no-print:
+    enabled: false
+    "#;
+        let res = parse_config_file(data).unwrap();
+        assert_eq!(
+            res.global_rule_overrides,
+            IndexMap::from([
+                (
+                    "python-security/no-eval".to_string(),
+                    RuleOverride {
+                        severity: Some(RuleSeverity::Error),
+                        category: Some(RuleCategory::Security),
+                        enabled: None,
+                    }
+                ),
+                (
+                    "python-best-practices/no-print".to_string(),
+                    RuleOverride {
+                        enabled: Some(false),
+                        ..Default::default()
+                    }
+                ),
+            ])
+        );
+        let serialized = config_file_to_yaml(&res).unwrap();
+        assert_eq!(parse_config_file(&serialized).unwrap(), res);
+
+        let data = r#"
+rulesets:
+  - python-security
+global-rule-overrides:
+  python-security/no-eval:
+    category: UNKNOWN
+    "#;
+        assert!(parse_config_file(data).is_err());
+    }
+
     // No ruleset available in the data means that we have no configuration file
     // whatsoever and we should return Err
     #[test]
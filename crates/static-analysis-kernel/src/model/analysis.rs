@@ -20,6 +20,7 @@ pub struct LinesToIgnore {
     pub lines_to_ignore_per_rule: HashMap<u32, Vec<String>>, // rules to ignore only for some files
     pub lines_to_ignore: Vec<u32>,                           // lines to ignore
     pub ignore_file: FileIgnoreBehavior,                     // apply to all the file
+    pub disabling_comment_lines: HashMap<u32, u32>, // line ignored -> line of the disabling comment
 }
 
 impl LinesToIgnore {
@@ -49,6 +50,31 @@ impl LinesToIgnore {
 
         false
     }
+
+    /// return the line of the comment that disables a specific rule
+    ///  - rule_name is the full rule name like rule1/rule2
+    ///  - line is the line of the violation
+    ///    returns None if the rule is not ignored for this line
+    pub fn disabling_comment_line(&self, rule_name: &str, line: u32) -> Option<u32> {
+        if !self.should_filter_rule(rule_name, line) {
+            return None;
+        }
+        let ignored_for_file = match &self.ignore_file {
+            AllRules => true,
+            FileIgnoreBehavior::SomeRules(rules) => rules.iter().any(|c| c == rule_name),
+        };
+        if ignored_for_file {
+            // The comment disabling rules for the whole file is always on the first line.
+            return Some(1);
+        }
+        // (If unknown, the disabling comment is assumed to be on the line above)
+        Some(
+            self.disabling_comment_lines
+                .get(&line)
+                .copied()
+                .unwrap_or(line.saturating_sub(1)),
+        )
+    }
 }
 
 // Used only internally
@@ -77,6 +103,71 @@ pub struct TreeSitterNode {
     pub children: Vec<TreeSitterNode>,
 }
 
+impl TreeSitterNode {
+    /// The depth at which [`Self::to_json_value`] truncates the tree.
+    pub const MAX_JSON_DEPTH: usize = 64;
+
+    /// Returns the 1-based start position of the node.
+    ///
+    /// The position is normalized from tree-sitter's 0-based position when the node is created
+    /// (see [`map_node`](crate::analysis::tree_sitter::map_node)), so no adjustment is needed.
+    pub fn start_position_1based(&self) -> Position {
+        self.start
+    }
+
+    /// Returns the 1-based end position of the node.
+    pub fn end_position_1based(&self) -> Position {
+        self.end
+    }
+
+    /// Returns this node and all of its descendants, in breadth-first order.
+    pub fn flatten(&self) -> Vec<&TreeSitterNode> {
+        let mut nodes = vec![self];
+        let mut idx = 0;
+        while idx < nodes.len() {
+            let node = nodes[idx];
+            nodes.extend(node.children.iter());
+            idx += 1;
+        }
+        nodes
+    }
+
+    /// Returns the nodes (including this one) that satisfy the `predicate`, in breadth-first order.
+    pub fn find_all<F: Fn(&TreeSitterNode) -> bool>(&self, predicate: F) -> Vec<&TreeSitterNode> {
+        self.flatten()
+            .into_iter()
+            .filter(|node| predicate(node))
+            .collect()
+    }
+
+    /// Serializes this node to JSON, truncating the tree after [`Self::MAX_JSON_DEPTH`] levels.
+    pub fn to_json_value(&self) -> serde_json::Value {
+        self.to_json_value_with_depth(Self::MAX_JSON_DEPTH)
+    }
+
+    /// Serializes this node to JSON, with the same shape as its `Serialize` implementation.
+    ///
+    /// Nodes nested `max_depth` levels below this one are replaced with a `{"truncated": true}`
+    /// sentinel, which bounds the recursion for deeply nested trees.
+    pub fn to_json_value_with_depth(&self, max_depth: usize) -> serde_json::Value {
+        if max_depth == 0 {
+            return serde_json::json!({ "truncated": true });
+        }
+        let children = self
+            .children
+            .iter()
+            .map(|child| child.to_json_value_with_depth(max_depth - 1))
+            .collect::<Vec<_>>();
+        serde_json::json!({
+            "astType": self.ast_type,
+            "start": self.start,
+            "end": self.end,
+            "fieldName": self.field_name,
+            "children": children,
+        })
+    }
+}
+
 // The node that is then passed to the visit function.
 #[derive(Clone, Debug, Serialize, Builder)]
 pub struct MatchNode {
@@ -86,10 +177,23 @@ pub struct MatchNode {
     pub context: MatchNodeContext,
 }
 
+impl MatchNode {
+    /// Returns a copy of this node whose `context.code` is set to `content`, the source of the
+    /// analyzed file. This allows callers to populate the context after the analysis.
+    pub fn with_context_code(&self, content: &str) -> MatchNode {
+        let mut match_node = self.clone();
+        match_node.context.code = Some(content.to_string());
+        match_node
+    }
+}
+
 #[cfg(test)]
 mod tests {
+    use crate::analysis::tree_sitter::{get_query, get_query_nodes, get_tree, map_node};
     use crate::model::analysis::FileIgnoreBehavior::SomeRules;
-    use crate::model::analysis::{FileIgnoreBehavior, LinesToIgnore};
+    use crate::model::analysis::{FileIgnoreBehavior, LinesToIgnore, TreeSitterNode};
+    use crate::model::common::Language;
+    use common::model::position::Position;
     use std::collections::HashMap;
 
     #[test]
@@ -101,6 +205,7 @@ mod tests {
             lines_to_ignore: vec![10, 42],
             lines_to_ignore_per_rule: lines_per_rule,
             ignore_file: FileIgnoreBehavior::SomeRules(vec![]),
+            disabling_comment_lines: HashMap::new(),
         };
 
         assert!(!lines_to_ignore.should_filter_rule("foo/bar", 11));
@@ -118,6 +223,7 @@ mod tests {
             lines_to_ignore: vec![],
             lines_to_ignore_per_rule: HashMap::new(),
             ignore_file: FileIgnoreBehavior::AllRules,
+            disabling_comment_lines: HashMap::new(),
         };
 
         assert!(lines_to_ignore.should_filter_rule("foo/bar", 11));
@@ -134,6 +240,7 @@ mod tests {
             lines_to_ignore: vec![],
             lines_to_ignore_per_rule: HashMap::new(),
             ignore_file: SomeRules(vec!["foo/bar".to_string()]),
+            disabling_comment_lines: HashMap::new(),
         };
 
         assert!(lines_to_ignore.should_filter_rule("foo/bar", 11));
@@ -143,4 +250,110 @@ mod tests {
         assert!(!lines_to_ignore.should_filter_rule("ruleset/rule", 13));
         assert!(lines_to_ignore.should_filter_rule("foo/bar", 13));
     }
+
+    #[test]
+    fn test_disabling_comment_line() {
+        let lines_to_ignore = LinesToIgnore {
+            lines_to_ignore: vec![10, 11],
+            lines_to_ignore_per_rule: HashMap::from([(20, vec!["ruleset/rule".to_string()])]),
+            ignore_file: SomeRules(vec!["foo/bar".to_string()]),
+            disabling_comment_lines: HashMap::from([(10, 8), (11, 8), (20, 19)]),
+        };
+
+        assert_eq!(
+            lines_to_ignore.disabling_comment_line("ruleset/rule", 10),
+            Some(8)
+        );
+        assert_eq!(
+            lines_to_ignore.disabling_comment_line("ruleset/rule", 11),
+            Some(8)
+        );
+        assert_eq!(
+            lines_to_ignore.disabling_comment_line("ruleset/rule", 20),
+            Some(19)
+        );
+        assert_eq!(
+            lines_to_ignore.disabling_comment_line("ruleset/rule", 12),
+            None
+        );
+        assert_eq!(
+            lines_to_ignore.disabling_comment_line("other/rule", 20),
+            None
+        );
+        // Rules ignored for the whole file are disabled by the first line.
+        assert_eq!(
+            lines_to_ignore.disabling_comment_line("foo/bar", 20),
+            Some(1)
+        );
+    }
+
+    #[test]
+    fn test_tree_sitter_node_flatten() {
+        let code = "def add(a, b):\n    return a + b\n";
+        let tree = get_tree(code, &Language::Python).unwrap();
+        let root = map_node(tree.root_node()).unwrap();
+
+        let nodes = root.flatten();
+        assert_eq!(nodes[0].ast_type, "module");
+        assert_eq!(nodes[1].ast_type, "function_definition");
+        // Breadth-first: all the children of `function_definition` come before any grandchild.
+        let kinds = nodes[2..5]
+            .iter()
+            .map(|n| n.ast_type.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(kinds, vec!["identifier", "parameters", "block"]);
+
+        // `add`, `a`, `b` (parameters), and `a`, `b` (return statement)
+        let identifiers = root.find_all(|n| n.ast_type == "identifier");
+        assert_eq!(identifiers.len(), 5);
+        assert_eq!(identifiers[0].start, Position::new(1, 5));
+    }
+
+    #[test]
+    fn test_match_node_with_context_code() {
+        let code = "x = 1\ny = foo(x)\n";
+        let tree = get_tree(code, &Language::Python).unwrap();
+        let query = get_query("(call function: (identifier) @fn)", &Language::Python).unwrap();
+        let mut match_node = get_query_nodes(&tree, &query, "file.py", code, &HashMap::new())
+            .pop()
+            .unwrap();
+        match_node.context.code = None;
+
+        let match_node = match_node.with_context_code(code);
+        let context_code = match_node.context.code.as_deref().unwrap();
+        assert_eq!(context_code, code);
+        let capture = &match_node.captures["fn"];
+        let start = capture.start.to_byte_offset(context_code).unwrap();
+        let end = capture.end.to_byte_offset(context_code).unwrap();
+        assert_eq!(&context_code[start..end], "foo");
+    }
+
+    #[test]
+    fn test_tree_sitter_node_to_json_value() {
+        let tree = get_tree("def foo(a):\n    return a\n", &Language::Python).unwrap();
+        let node = map_node(tree.root_node()).unwrap();
+        assert_eq!(node.to_json_value(), serde_json::to_value(&node).unwrap());
+
+        // A tree that is 100 levels deep is truncated at `MAX_JSON_DEPTH`.
+        let mut deep = TreeSitterNode {
+            ast_type: "leaf".to_string(),
+            start: Position::new(1, 1),
+            end: Position::new(1, 2),
+            field_name: None,
+            children: vec![],
+        };
+        for _ in 0..99 {
+            deep = TreeSitterNode {
+                ast_type: "parent".to_string(),
+                children: vec![deep.clone()],
+                ..deep
+            };
+        }
+        let mut value = &deep.to_json_value();
+        for _ in 0..TreeSitterNode::MAX_JSON_DEPTH {
+            assert_eq!(value["astType"], "parent");
+            value = &value["children"][0];
+        }
+        assert_eq!(value, &serde_json::json!({ "truncated": true }));
+    }
 }
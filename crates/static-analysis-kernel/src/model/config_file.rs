@@ -79,6 +79,8 @@ pub struct RuleConfig {
     pub severity: Option<BySubtree<RuleSeverity>>,
     // Override this rule's category.
     pub category: Option<RuleCategory>,
+    // User-defined tags attached to this rule's findings.
+    pub custom_tags: Vec<String>,
 }
 
 // Configuration for a ruleset.
@@ -90,6 +92,17 @@ pub struct RulesetConfig {
     pub rules: IndexMap<String, RuleConfig>,
 }
 
+// Overrides for a rule that apply regardless of its ruleset's configuration.
+#[derive(Debug, PartialEq, Default, Clone)]
+pub struct RuleOverride {
+    // Override this rule's severity, unless its rule configuration already does.
+    pub severity: Option<RuleSeverity>,
+    // Override this rule's category, unless its rule configuration already does.
+    pub category: Option<RuleCategory>,
+    // Enable or disable this rule.
+    pub enabled: Option<bool>,
+}
+
 #[derive(Debug, Clone)]
 pub enum ConfigMethod {
     File,
@@ -110,6 +123,49 @@ pub struct ConfigFile {
     pub max_file_size_kb: Option<u64>,
     // Do not analyze generated files.
     pub ignore_generated_files: Option<bool>,
+    // Rulesets that must be found when the rulesets are resolved; a missing one is a hard error.
+    pub required_rulesets: Vec<String>,
+    // Overrides for rules by their full name (`ruleset/rule`), with a lower precedence than the
+    // rule configurations in `rulesets`.
+    pub global_rule_overrides: IndexMap<String, RuleOverride>,
+}
+
+impl ConfigFile {
+    // Returns the required rulesets that are not in `available_rulesets`.
+    pub fn missing_required_rulesets(&self, available_rulesets: &[&str]) -> Vec<&str> {
+        self.required_rulesets
+            .iter()
+            .map(String::as_str)
+            .filter(|rs| !available_rulesets.contains(rs))
+            .collect()
+    }
+
+    // Returns a copy of this configuration with the command-line overrides applied on top.
+    pub fn with_overrides(&self, overrides: &CliOverrides) -> ConfigFile {
+        let mut config = self.clone();
+        if let Some(max_file_size_kb) = overrides.max_file_size_kb {
+            config.max_file_size_kb = Some(max_file_size_kb);
+        }
+        if let Some(ignore_gitignore) = overrides.ignore_gitignore {
+            config.ignore_gitignore = Some(ignore_gitignore);
+        }
+        config
+            .paths
+            .ignore
+            .extend(overrides.extra_ignore_paths.iter().cloned());
+        config
+    }
+}
+
+// Values passed on the command line that take precedence over the configuration file.
+#[derive(Debug, Default, Clone)]
+pub struct CliOverrides {
+    // Replaces `max-file-size-kb` when set.
+    pub max_file_size_kb: Option<u64>,
+    // Replaces `ignore-gitignore` when set.
+    pub ignore_gitignore: Option<bool>,
+    // Appended to the ignored paths.
+    pub extra_ignore_paths: Vec<PathPattern>,
 }
 
 impl fmt::Display for ConfigFile {
@@ -126,6 +182,76 @@ impl PathPattern {
             .unwrap_or(false)
             || Path::new(path).starts_with(&self.prefix)
     }
+
+    // Returns a regular expression that matches the same paths as `matches`.
+    // The expression uses the syntax of the `regex` crate and, like the glob it's derived from,
+    // is meant to be matched against the bytes of a path.
+    pub fn as_regex_str(&self) -> String {
+        let prefix = self.prefix.to_string_lossy();
+        let prefix = prefix.trim_end_matches('/');
+        let prefix_regex = if prefix.is_empty() {
+            "^.*$".to_string()
+        } else {
+            format!("^{}(?:/.*)?$", escape_regex(prefix))
+        };
+        match &self.glob {
+            Some(glob) => format!("(?:{})|(?:{})", glob.glob().regex(), prefix_regex),
+            None => prefix_regex,
+        }
+    }
+
+    // Translates a line of a `.gitignore` file to a pattern that matches the same paths.
+    // Returns `None` for blank lines and comments, and for negated (`!`) patterns, which cannot be
+    // expressed as an ignored path.
+    pub fn from_gitignore_line(line: &str) -> Option<PathPattern> {
+        let line = line.trim_end();
+        if line.is_empty() || line.starts_with('#') || line.starts_with('!') {
+            return None;
+        }
+        // A leading backslash escapes a literal `#` or `!`.
+        let line = line
+            .strip_prefix('\\')
+            .filter(|l| l.starts_with(['#', '!']))
+            .unwrap_or(line);
+        // A trailing separator only matches directories.
+        let (pattern, directory_only) = match line.strip_suffix('/') {
+            Some(pattern) => (pattern, true),
+            None => (line, false),
+        };
+        // A separator at the beginning or in the middle makes the pattern relative to the root;
+        // otherwise, it matches at any depth.
+        let anchored = pattern.contains('/');
+        let pattern = pattern.strip_prefix('/').unwrap_or(pattern);
+        if pattern.is_empty() {
+            return None;
+        }
+        // Braces are literal in `.gitignore` files, but they delimit alternatives in a glob.
+        let pattern = pattern.replace('{', "\\{").replace('}', "\\}");
+        let pattern = if anchored {
+            pattern
+        } else {
+            format!("**/{}", pattern)
+        };
+        // A matching directory also excludes everything it contains.
+        let glob = if directory_only {
+            format!("{}/**", pattern)
+        } else {
+            format!("{}{{,/**}}", pattern)
+        };
+        Some(PathPattern::from(glob))
+    }
+}
+
+// Escapes all the characters of `text` that have a special meaning in a regular expression.
+fn escape_regex(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        if "\\.+*?()|[]{}^$#&-~".contains(c) {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+    escaped
 }
 
 impl From<String> for PathPattern {
@@ -163,6 +289,17 @@ impl PartialEq for PathPattern {
 }
 
 impl PathConfig {
+    // Returns a configuration that ignores the paths excluded by a `.gitignore` file.
+    pub fn from_gitignore_file(content: &str) -> PathConfig {
+        PathConfig {
+            only: None,
+            ignore: content
+                .lines()
+                .filter_map(PathPattern::from_gitignore_line)
+                .collect(),
+        }
+    }
+
     pub fn allows_file(&self, file_name: &str) -> bool {
         !self.ignore.iter().any(|pattern| pattern.matches(file_name))
             && match &self.only {
@@ -217,3 +354,163 @@ where
     }
     out
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{CliOverrides, ConfigFile, PathConfig, PathPattern};
+
+    #[test]
+    fn test_path_pattern_from_gitignore_line() {
+        for line in ["", "   ", "# comment", "!keep.py", "/"] {
+            assert!(PathPattern::from_gitignore_line(line).is_none(), "{}", line);
+        }
+
+        let cases = [
+            // Patterns without a separator match at any depth.
+            ("*.so", vec!["lib.so", "a/b/lib.so"], vec!["lib.so.txt"]),
+            (
+                "build",
+                vec!["build", "build/out.py", "src/build/out.py"],
+                vec!["builds/out.py"],
+            ),
+            // A leading separator anchors the pattern to the root.
+            (
+                "/build",
+                vec!["build", "build/out.py"],
+                vec!["src/build/out.py"],
+            ),
+            (
+                "doc/frotz",
+                vec!["doc/frotz/a.md"],
+                vec!["a/doc/frotz/a.md"],
+            ),
+            // A trailing separator only matches directories.
+            (
+                "logs/",
+                vec!["logs/a.txt", "src/logs/a.txt"],
+                vec!["logs", "src/logs"],
+            ),
+            ("/out/", vec!["out/a.txt"], vec!["out", "src/out/a.txt"]),
+            // Escaped characters and literal braces.
+            ("\\#notes", vec!["#notes"], vec!["notes"]),
+            ("\\!important", vec!["!important"], vec!["important"]),
+            ("{a,b}.py", vec!["{a,b}.py"], vec!["a.py", "b.py"]),
+        ];
+        for (line, matched, not_matched) in cases {
+            let pattern = PathPattern::from_gitignore_line(line).unwrap();
+            for path in matched {
+                assert!(pattern.matches(path), "`{}` should match `{}`", line, path);
+            }
+            for path in not_matched {
+                assert!(
+                    !pattern.matches(path),
+                    "`{}` should not match `{}`",
+                    line,
+                    path
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_path_config_from_gitignore_file() {
+        let config = PathConfig::from_gitignore_file("# Build output\n/target/\n\n!.env\n*.pyc\n");
+        assert_eq!(config.only, None);
+        assert_eq!(config.ignore.len(), 2);
+        assert!(!config.allows_file("target/debug/main"));
+        assert!(!config.allows_file("src/__pycache__/main.pyc"));
+        assert!(config.allows_file("src/main.py"));
+        assert!(config.allows_file(".env"));
+    }
+
+    // The regex built by `as_regex_str` matches the same paths as the pattern.
+    #[test]
+    fn test_path_pattern_as_regex_str() {
+        let patterns = ["**/*.py", "src/**", "!test", "src", "*.md", "docs/"];
+        let paths = [
+            "main.py",
+            "src/main.py",
+            "src/nested/dir/MAIN.PY",
+            "src",
+            "srcs/main.rs",
+            "!test",
+            "!test/file.js",
+            "test/file.js",
+            "README.md",
+            "docs/README.md",
+            "docs",
+            "documentation/index.html",
+        ];
+        for pattern in patterns {
+            let path_pattern = PathPattern::from(pattern.to_string());
+            let regex = regex::bytes::Regex::new(&path_pattern.as_regex_str()).unwrap();
+            for path in paths {
+                assert_eq!(
+                    regex.is_match(path.as_bytes()),
+                    path_pattern.matches(path),
+                    "pattern `{}` and its regex `{}` disagree on `{}`",
+                    pattern,
+                    regex,
+                    path
+                );
+            }
+        }
+    }
+
+    fn base_config() -> ConfigFile {
+        ConfigFile {
+            ignore_gitignore: Some(false),
+            max_file_size_kb: Some(200),
+            paths: super::PathConfig {
+                only: None,
+                ignore: vec!["vendor".to_string().into()],
+            },
+            ..Default::default()
+        }
+    }
+
+    // No overrides leaves the configuration unchanged.
+    #[test]
+    fn test_with_overrides_empty() {
+        let config = base_config();
+        assert_eq!(config.with_overrides(&CliOverrides::default()), config);
+    }
+
+    #[test]
+    fn test_with_overrides_max_file_size_kb() {
+        let overrides = CliOverrides {
+            max_file_size_kb: Some(42),
+            ..Default::default()
+        };
+        let config = base_config().with_overrides(&overrides);
+        assert_eq!(config.max_file_size_kb, Some(42));
+        assert_eq!(config.ignore_gitignore, Some(false));
+    }
+
+    #[test]
+    fn test_with_overrides_ignore_gitignore() {
+        let overrides = CliOverrides {
+            ignore_gitignore: Some(true),
+            ..Default::default()
+        };
+        let config = base_config().with_overrides(&overrides);
+        assert_eq!(config.ignore_gitignore, Some(true));
+        assert_eq!(config.max_file_size_kb, Some(200));
+    }
+
+    #[test]
+    fn test_with_overrides_extra_ignore_paths() {
+        let overrides = CliOverrides {
+            extra_ignore_paths: vec!["src/legacy/**".to_string().into()],
+            ..Default::default()
+        };
+        let config = base_config().with_overrides(&overrides);
+        assert_eq!(
+            config.paths.ignore,
+            vec![
+                PathPattern::from("vendor".to_string()),
+                PathPattern::from("src/legacy/**".to_string())
+            ]
+        );
+    }
+}
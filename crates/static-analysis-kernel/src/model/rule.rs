@@ -4,7 +4,7 @@ use base64::Engine;
 
 use crate::analysis::tree_sitter::{get_query, TSQuery};
 use crate::model::rule_test::RuleTest;
-use crate::model::violation::Violation;
+use crate::model::violation::{SuppressedViolation, Violation};
 use common::model::diff_aware::DiffAware;
 use derive_builder::Builder;
 use serde::{Deserialize, Serialize};
@@ -49,7 +49,7 @@ impl fmt::Display for RuleCategory {
     }
 }
 
-#[derive(Copy, Clone, Deserialize, Debug, Serialize, Eq, PartialEq)]
+#[derive(Copy, Clone, Deserialize, Debug, Serialize, Eq, PartialEq, Hash)]
 pub enum RuleSeverity {
     #[serde(rename = "ERROR")]
     Error,
@@ -94,6 +94,10 @@ pub enum RuleType {
     Regex,
     #[serde(rename = "TREE_SITTER_QUERY")]
     TreeSitterQuery,
+    /// A rule that is executed once per file with the file's raw contents, rather than once
+    /// per tree-sitter query match.
+    #[serde(rename = "JAVASCRIPT")]
+    JavaScript,
 }
 
 #[derive(Copy, Clone, Deserialize, Debug, Serialize, Eq, PartialEq)]
@@ -177,7 +181,9 @@ pub struct RuleInternal {
     pub severity: RuleSeverity,
     pub language: Language,
     pub code: String,
-    pub tree_sitter_query: TSQuery,
+    /// The query used to generate the captures passed to the rule. This is `None` for
+    /// [`RuleType::JavaScript`] rules, which are executed once for the entire file.
+    pub tree_sitter_query: Option<TSQuery>,
 }
 
 // This error is meant to be used when we try to convert a Rule to a RuleInternal
@@ -241,7 +247,10 @@ impl Rule {
 
     // convert the rule to rule internal
     pub fn to_rule_internal(&self) -> Result<RuleInternal, RuleInternalError> {
-        if self.rule_type != RuleType::TreeSitterQuery {
+        if !matches!(
+            self.rule_type,
+            RuleType::TreeSitterQuery | RuleType::JavaScript
+        ) {
             return Err(RuleInternalError::InvalidRuleType(self.rule_type));
         }
         let description = self.decode_description()?;
@@ -262,17 +271,22 @@ impl Rule {
             })
             .transpose()?;
 
-        let tree_sitter_query = String::from_utf8(
-            general_purpose::STANDARD
-                .decode(
-                    self.tree_sitter_query_base64
-                        .as_ref()
-                        .ok_or(RuleInternalError::MissingTreeSitterQuery)?,
-                )
-                .map_err(|e| RuleInternalError::InvalidBase64(e.to_string()))?,
-        )?;
-        let tree_sitter_query = get_query(&tree_sitter_query, &self.language)
-            .map_err(|e| RuleInternalError::InvalidTreeSitterQuery(Box::new(e)))?;
+        let tree_sitter_query = if self.rule_type == RuleType::JavaScript {
+            None
+        } else {
+            let tree_sitter_query = String::from_utf8(
+                general_purpose::STANDARD
+                    .decode(
+                        self.tree_sitter_query_base64
+                            .as_ref()
+                            .ok_or(RuleInternalError::MissingTreeSitterQuery)?,
+                    )
+                    .map_err(|e| RuleInternalError::InvalidBase64(e.to_string()))?,
+            )?;
+            let tree_sitter_query = get_query(&tree_sitter_query, &self.language)
+                .map_err(|e| RuleInternalError::InvalidTreeSitterQuery(Box::new(e)))?;
+            Some(tree_sitter_query)
+        };
 
         Ok(RuleInternal {
             name: self.name.clone(),
@@ -317,6 +331,14 @@ pub struct RuleResult {
     pub rule_name: String,
     pub filename: String,
     pub violations: Vec<Violation>,
+    /// The violations that were suppressed by a comment in the source code.
+    #[builder(default)]
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub suppressed_violations: Vec<SuppressedViolation>,
+    /// The user-defined tags from the rule's configuration.
+    #[builder(default)]
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub custom_tags: Vec<String>,
     pub errors: Vec<String>,
     pub execution_error: Option<String>,
     pub output: Option<String>,
@@ -456,6 +478,40 @@ mod tests {
         assert!(fixed_ruled.cwe.is_some());
     }
 
+    /// JavaScript rules don't require a tree-sitter query.
+    #[test]
+    fn test_to_rule_internal_javascript() {
+        let rule = Rule {
+            name: "myrule".to_string(),
+            short_description_base64: None,
+            description_base64: None,
+            category: RuleCategory::BestPractices,
+            severity: RuleSeverity::Warning,
+            language: Language::Python,
+            rule_type: RuleType::JavaScript,
+            entity_checked: None,
+            code_base64: encode_base64_string("function visit(file) {}".to_string()),
+            checksum: "foobar".to_string(),
+            pattern: None,
+            cwe: None,
+            tree_sitter_query_base64: None,
+            arguments: vec![],
+            tests: vec![],
+            is_testing: false,
+        };
+        let rule_internal = rule.to_rule_internal().unwrap();
+        assert!(rule_internal.tree_sitter_query.is_none());
+
+        let rule = Rule {
+            rule_type: RuleType::TreeSitterQuery,
+            ..rule
+        };
+        assert!(matches!(
+            rule.to_rule_internal(),
+            Err(RuleInternalError::MissingTreeSitterQuery)
+        ));
+    }
+
     #[test]
     fn absolute_path_err() {
         fn builder_with(filename: &str) -> RuleResultBuilder {
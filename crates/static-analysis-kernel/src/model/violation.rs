@@ -38,4 +38,43 @@ pub struct Violation {
     pub fixes: Vec<Fix>,
     /// An ordered list of regions representing a flow from start to finish.
     pub taint_flow: Option<Vec<Region>>,
+    /// The code flows reported for this violation via `Violation.addCodeFlow`.
+    #[builder(default)]
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub code_flows: Vec<CodeFlow>,
+    /// The related locations reported for this violation via `Violation.addRelatedLocation`.
+    #[builder(default)]
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub related_locations: Vec<RelatedLocation>,
+}
+
+/// A violation that was suppressed by a `no-dd-sa` or `datadog-disable` comment.
+#[derive(Deserialize, Debug, Serialize, Clone)]
+pub struct SuppressedViolation {
+    pub violation: Violation,
+    /// The line of the comment that suppressed the violation.
+    pub comment_line: u32,
+}
+
+/// A single step of a [`CodeFlow`].
+#[derive(Deserialize, Debug, Serialize, Clone, PartialEq, Eq, Hash)]
+pub struct CodeFlowLocation {
+    pub file: String,
+    pub line: u32,
+    pub col: u32,
+    pub message: String,
+}
+
+/// An ordered list of code locations that together constitute a violation (for example, the
+/// steps from a source to a sink), reported by a rule via `Violation.addCodeFlow`.
+pub type CodeFlow = Vec<CodeFlowLocation>;
+
+/// A secondary location referenced by a rule's message (for example, "see also ..."),
+/// reported by a rule via `Violation.addRelatedLocation`.
+#[derive(Deserialize, Debug, Serialize, Clone, PartialEq, Eq, Hash)]
+pub struct RelatedLocation {
+    pub message: String,
+    pub file: String,
+    pub line: u32,
+    pub col: u32,
 }
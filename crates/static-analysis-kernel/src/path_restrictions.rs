@@ -173,6 +173,7 @@ mod tests {
                             arguments: Default::default(),
                             severity: None,
                             category: None,
+                            custom_tags: vec![],
                         },
                     ),
                     (
@@ -185,6 +186,7 @@ mod tests {
                             arguments: Default::default(),
                             severity: None,
                             category: None,
+                            custom_tags: vec![],
                         },
                     ),
                     (
@@ -197,6 +199,7 @@ mod tests {
                             arguments: Default::default(),
                             severity: None,
                             category: None,
+                            custom_tags: vec![],
                         },
                     ),
                 ]),
@@ -236,6 +239,7 @@ mod tests {
                         arguments: Default::default(),
                         severity: None,
                         category: None,
+                        custom_tags: vec![],
                     },
                 )]),
             },
@@ -254,6 +258,7 @@ mod tests {
                         arguments: Default::default(),
                         severity: None,
                         category: None,
+                        custom_tags: vec![],
                     },
                 )]),
             },
@@ -274,6 +279,7 @@ mod tests {
                         arguments: Default::default(),
                         severity: None,
                         category: None,
+                        custom_tags: vec![],
                     },
                 )]),
             },
@@ -292,6 +298,7 @@ mod tests {
                         arguments: Default::default(),
                         severity: None,
                         category: None,
+                        custom_tags: vec![],
                     },
                 )]),
             },
@@ -326,6 +333,7 @@ mod tests {
                         arguments: Default::default(),
                         severity: None,
                         category: None,
+                        custom_tags: vec![],
                     },
                 )]),
             },
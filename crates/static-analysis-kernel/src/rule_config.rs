@@ -34,11 +34,19 @@ impl RuleConfigProvider {
 
 impl DiffAware for RuleConfigProvider {
     fn generate_diff_aware_digest(&self) -> String {
-        format!(
+        let digest = format!(
             "{}:{}",
             self.path_restrictions.generate_diff_aware_digest(),
             self.argument_provider.generate_diff_aware_digest()
-        )
+        );
+        // Only append the overrides digest when there is one, so that configurations without
+        // custom tags or disabled rules keep the same digest.
+        let overrides = self.rule_overrides.generate_diff_aware_digest();
+        if overrides.is_empty() {
+            digest
+        } else {
+            format!("{}:{}", digest, overrides)
+        }
     }
 }
 
@@ -50,9 +58,11 @@ pub struct RuleConfig<'a> {
 
 impl<'a> RuleConfig<'a> {
     pub fn rule_is_enabled(&self, rule_name: &str) -> bool {
-        self.provider
-            .path_restrictions
-            .rule_applies(rule_name, &self.file_path)
+        self.provider.rule_overrides.is_enabled(rule_name)
+            && self
+                .provider
+                .path_restrictions
+                .rule_applies(rule_name, &self.file_path)
     }
 
     pub fn get_arguments(&self, rule_name: &str) -> HashMap<String, String> {
@@ -70,6 +80,10 @@ impl<'a> RuleConfig<'a> {
     pub fn get_category(&self, rule_name: &str) -> Option<RuleCategory> {
         self.provider.rule_overrides.category(rule_name)
     }
+
+    pub fn get_custom_tags(&self, rule_name: &str) -> &[String] {
+        self.provider.rule_overrides.custom_tags(rule_name)
+    }
 }
 
 impl Default for RuleConfig<'static> {
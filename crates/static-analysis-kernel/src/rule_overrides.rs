@@ -1,5 +1,6 @@
-use crate::model::config_file::{BySubtree, ConfigFile, SplitPath};
+use crate::model::config_file::{BySubtree, ConfigFile, RuleOverride, SplitPath};
 use crate::model::rule::{RuleCategory, RuleSeverity};
+use common::model::diff_aware::DiffAware;
 use std::collections::HashMap;
 
 /// User-provided overrides for rule definitions.
@@ -7,6 +8,8 @@ use std::collections::HashMap;
 pub struct RuleOverrides {
     severities: HashMap<String, BySubtree<RuleSeverity>>,
     categories: HashMap<String, RuleCategory>,
+    custom_tags: HashMap<String, Vec<String>>,
+    global: HashMap<String, RuleOverride>,
 }
 
 impl RuleOverrides {
@@ -34,9 +37,30 @@ impl RuleOverrides {
                 })
             })
             .collect();
+        let custom_tags: HashMap<String, Vec<String>> = cfg
+            .rulesets
+            .iter()
+            .flat_map(|(rs_name, cfg)| {
+                cfg.rules.iter().filter_map(move |(rule_name, rule)| {
+                    (!rule.custom_tags.is_empty()).then(|| {
+                        (
+                            format!("{}/{}", rs_name, rule_name),
+                            rule.custom_tags.clone(),
+                        )
+                    })
+                })
+            })
+            .collect();
+        let global: HashMap<String, RuleOverride> = cfg
+            .global_rule_overrides
+            .iter()
+            .map(|(rule_name, rule_override)| (rule_name.clone(), rule_override.clone()))
+            .collect();
         RuleOverrides {
             severities,
             categories,
+            custom_tags,
+            global,
         }
     }
 
@@ -45,10 +69,48 @@ impl RuleOverrides {
         self.severities
             .get(rule_name)
             .and_then(|s| s.get_ancestor(file_path).cloned())
+            .or_else(|| self.global.get(rule_name).and_then(|o| o.severity))
     }
 
     // Returns the overridden category for the given rule name, or the original category if no override exists.
     pub fn category(&self, rule_name: &str) -> Option<RuleCategory> {
-        self.categories.get(rule_name).copied()
+        self.categories
+            .get(rule_name)
+            .copied()
+            .or_else(|| self.global.get(rule_name).and_then(|o| o.category))
+    }
+
+    // Returns whether the given rule is enabled; rules are enabled unless overridden.
+    pub fn is_enabled(&self, rule_name: &str) -> bool {
+        self.global
+            .get(rule_name)
+            .and_then(|o| o.enabled)
+            .unwrap_or(true)
+    }
+
+    // Returns the user-defined tags for the given rule name (empty if there are none).
+    pub fn custom_tags(&self, rule_name: &str) -> &[String] {
+        self.custom_tags
+            .get(rule_name)
+            .map(Vec::as_slice)
+            .unwrap_or_default()
+    }
+}
+
+impl DiffAware for RuleOverrides {
+    fn generate_diff_aware_digest(&self) -> String {
+        let mut digests = self
+            .custom_tags
+            .iter()
+            .map(|(rule_name, tags)| format!("{}:{}", rule_name, tags.join(",")))
+            .chain(
+                self.global
+                    .iter()
+                    .filter(|(_, o)| !o.enabled.unwrap_or(true))
+                    .map(|(rule_name, _)| format!("{}:disabled", rule_name)),
+            )
+            .collect::<Vec<_>>();
+        digests.sort();
+        digests.join(";")
     }
 }
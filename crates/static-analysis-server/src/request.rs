@@ -5,7 +5,7 @@ use crate::constants::{
 use crate::model::analysis_request::AnalysisRequest;
 use crate::model::analysis_response::RuleResponse;
 use crate::model::violation::ServerViolation;
-use common::analysis_options::AnalysisOptions;
+use common::analysis_options::{AnalysisOptions, RuleTimeoutAction};
 use kernel::analysis::analyze::analyze_with;
 use kernel::analysis::ddsa_lib::JsRuntime;
 use kernel::config_file::parse_config_file;
@@ -100,6 +100,8 @@ pub fn process_analysis_request<T: Borrow<RuleInternal>>(
                 .unwrap_or(false),
             ignore_generated_files: false,
             timeout,
+            rule_timeout_action: RuleTimeoutAction::default(),
+            source_map_directory: None,
         },
     );
 